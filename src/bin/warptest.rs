@@ -0,0 +1,138 @@
+// Renders a warp's histogram next to its integrated pdf, and a scatter plot
+// of its samples, over the chart used by the chi-squared test:
+//
+//     warptest <warp> [samples] [parameter]
+//
+// writes <warp>-histogram.pfm, <warp>-histogram.ppm and <warp>-scatter.ppm
+// to the working directory
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::process;
+
+use graphite::*;
+
+const RES: A2<usize> = A2(64, 64);
+const GAP: usize = 4;
+const SCATTER_RES: usize = 512;
+const SCATTER_POINTS: usize = 20_000;
+
+const WARPS: [&str; 18] = ["triangle", "disk", "bilinear", "cosine-hemisphere", "cylinder",
+                           "sphere", "hemisphere", "cone", "spherical-triangle",
+                           "spherical-rectangle", "beckmann", "trowbridge-reitz",
+                           "trowbridge-reitz-visible", "trowbridge-reitz-visible-cap", "isotropic",
+                           "henyey-greenstein", "two-lobe-henyey-greenstein", "rayleigh"];
+
+struct Image {
+    width:  usize,
+    height: usize,
+    data:   Vec<F>,
+}
+
+impl Image {
+    fn new(width: usize, height: usize, v: F) -> Self
+    { Self { width, height, data: vec![v; width * height] } }
+
+    // Origin at the bottom left
+    fn set(&mut self, x: usize, y: usize, v: F) { self.data[y * self.width + x] = v; }
+
+    // F may be f64
+    #[allow(clippy::unnecessary_cast)]
+    fn write_pfm(&self, path: &str) -> io::Result<()> {
+        let mut f = BufWriter::new(File::create(path)?);
+        write!(f, "Pf\n{} {}\n-1\n", self.width, self.height)?;
+        for v in &self.data { f.write_all(&(*v as f32).to_le_bytes())?; }
+        f.flush()
+    }
+
+    fn write_ppm(&self, path: &str, scale: F) -> io::Result<()> {
+        let mut f = BufWriter::new(File::create(path)?);
+        write!(f, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for row in self.data.chunks(self.width).rev() {
+            for v in row {
+                let c = ((v * scale).clamp(0., 1.) * 255.).round() as u8;
+                f.write_all(&[c, c, c])?;
+            }
+        }
+        f.flush()
+    }
+}
+
+fn samples(n: usize) -> impl Iterator<Item=F2> {
+    let mut rng = Pcg32::default();
+    (0..n).map(move |_| rng.next_f2())
+}
+
+fn run<W>(name: &str, w: &W, p: W::Param, n: usize) -> io::Result<()>
+    where W: Warp<Sample=F2>, W::Output: Chart
+{
+    let test = ChiSquareTest::new(w, p, RES, samples(n));
+    match test.run(0.01, 1) {
+        Ok(p) => println!("{}: accepted with p-value {}", name, p),
+        Err(e) => println!("{}: {}", name, e),
+    }
+
+    // observed and expected densities side by side
+    let density = F::of((RES.0 * RES.1) as I) / (F::of(n as I) * W::Output::JACOBIAN);
+    let mut hist = Image::new(2 * RES.0 + GAP, RES.1, 0.);
+    let mut max: F = 0.;
+    for j in 0..RES.1 {
+        for i in 0..RES.0 {
+            let k = j * RES.0 + i;
+            let (o, e) = (test.observed[k] * density, test.expected[k] * density);
+            hist.set(i, j, o);
+            hist.set(RES.0 + GAP + i, j, e);
+            max = max.max(o).max(e);
+        }
+    }
+    hist.write_pfm(&format!("{}-histogram.pfm", name))?;
+    hist.write_ppm(&format!("{}-histogram.ppm", name), if max > 0. { max.inv() } else { 1. })?;
+
+    let mut scatter = Image::new(SCATTER_RES, SCATTER_RES, 1.);
+    for s in samples(n.min(SCATTER_POINTS)) {
+        let u = w.warp(s, p).chart() * F::of(SCATTER_RES as I);
+        if u[X] >= 0. && u[Y] >= 0. {
+            let (x, y) = (u[X] as usize, u[Y] as usize);
+            scatter.set(x.min(SCATTER_RES - 1), y.min(SCATTER_RES - 1), 0.);
+        }
+    }
+    scatter.write_ppm(&format!("{}-scatter.ppm", name), 1.)
+}
+
+fn usage() -> ! {
+    eprintln!("usage: warptest <warp> [samples] [parameter]\nwarps: {}", WARPS.join(", "));
+    process::exit(2)
+}
+
+fn main() -> io::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let name = args.first().map_or_else(|| usage(), String::as_str);
+    let n = args.get(1).map_or(Ok(1_000_000), |a| a.parse()).unwrap_or_else(|_| usage());
+    let param = |d: F| args.get(2).map_or(Ok(d), |a| a.parse()).unwrap_or_else(|_| usage());
+
+    match name {
+        "triangle" => run(name, &UniformTriangle, (), n),
+        "disk" => run(name, &UniformDisk, (), n),
+        "bilinear" => run(name, &Bilinear, A4(1., 0.2, 3., 0.5), n),
+        "cosine-hemisphere" => run(name, &CosineHemisphere, (), n),
+        "cylinder" => run(name, &UniformCylinder, (), n),
+        "sphere" => run(name, &UniformSphere, (), n),
+        "hemisphere" => run(name, &UniformHemisphere, (), n),
+        "cone" => run(name, &UniformCone, param(0.8), n),
+        "spherical-triangle" => run(name, &SphericalTriangle, EXAMPLE_TRIANGLE, n),
+        "spherical-rectangle" => run(name, &SphericalRectangle, EXAMPLE_RECTANGLE, n),
+        "beckmann" => run(name, &BeckmannHemisphere, param(0.3), n),
+        "trowbridge-reitz" => run(name, &TrowbridgeReitzHemisphere, param(0.3), n),
+        "trowbridge-reitz-visible" =>
+            run(name, &TrowbridgeReitzVisible, (EXAMPLE_WO, F2::rep(param(0.3))), n),
+        "trowbridge-reitz-visible-cap" =>
+            run(name, &TrowbridgeReitzVisibleCap, (EXAMPLE_WO, F2::rep(param(0.3))), n),
+        "isotropic" => run(name, &IsotropicPhase, (), n),
+        "henyey-greenstein" => run(name, &HenyeyGreenstein, (param(0.5), EXAMPLE_WO), n),
+        "two-lobe-henyey-greenstein" =>
+            run(name, &TwoLobeHenyeyGreenstein, (A2(param(0.6), -0.4), 0.3, EXAMPLE_WO), n),
+        "rayleigh" => run(name, &Rayleigh, EXAMPLE_WO, n),
+        _ => usage(),
+    }
+}
@@ -0,0 +1,126 @@
+use std::convert::TryFrom;
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Bin {
+    q:     F,
+    alias: usize,
+    pmf:   F,
+}
+
+// Walker's alias method (with Vose's construction) for constant-time
+// sampling of a discrete distribution; all-zero weights are sampled uniformly
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize))]
+#[cfg_attr(feature="serde-derive", serde(try_from="Vec<F>"))]
+pub struct AliasTable {
+    bins: Vec<Bin>,
+}
+
+impl AliasTable {
+    /// # Panics
+    /// If `weights` is empty
+    pub fn new(weights: &[F]) -> Self {
+        assert!(!weights.is_empty(), "alias table over no weights");
+        let sum = weights.iter().map(|w| w.abs()).sum::<F>();
+        let n = F::of(weights.len() as I);
+        let mut bins: Vec<_> = weights.iter().map(|w| {
+            let pmf = if sum > 0. { w.abs() / sum } else { n.inv() };
+            Bin { q: pmf * n, alias: 0, pmf }
+        }).collect();
+
+        let (mut small, mut large): (Vec<_>, Vec<_>) =
+            (0..bins.len()).partition(|&i| bins[i].q < 1.);
+        while let (Some(&s), Some(&l)) = (small.last(), large.last()) {
+            small.pop();
+            bins[s].alias = l;
+            bins[l].q -= 1. - bins[s].q;
+            if bins[l].q < 1. { large.pop(); small.push(l); }
+        }
+        // leftovers are 1 up to rounding
+        for i in small.into_iter().chain(large) { bins[i].q = 1.; }
+        Self { bins }
+    }
+
+    #[inline(always)] pub const fn count(&self) -> usize { self.bins.len() }
+
+    #[inline(always)] pub fn pmf(&self, i: usize) -> F { self.bins[i].pmf }
+
+    // Index and its probability
+    #[inline(always)] pub fn sample(&self, s: F) -> (usize, F) {
+        let (i, pmf, _) = self.sample_remapped(s);
+        (i, pmf)
+    }
+
+    // Index, its probability and the leftover fraction of the sample
+    // remapped to [0, 1) for reuse
+    #[inline] pub fn sample_remapped(&self, s: F) -> (usize, F, F) {
+        let n = self.count() as I;
+        let i = F::discrete(s.clamp(0., 1.), n) as usize;
+        let t = s.mul_add(F::of(n), -F::of(i as I)).clamp(0., F::ONE_MINUS_EPS);
+        let bin = &self.bins[i];
+        let (index, rest) = if t < bin.q { (i, t / bin.q) }
+                            else { (bin.alias, (t - bin.q) / (1. - bin.q)) };
+        (index, self.pmf(index), rest.min(F::ONE_MINUS_EPS))
+    }
+}
+
+impl TryFrom<Vec<F>> for AliasTable {
+    type Error = &'static str;
+    #[inline] fn try_from(weights: Vec<F>) -> Result<Self, Self::Error> {
+        if weights.is_empty() { return Err("alias table over no weights") }
+        Ok(Self::new(&weights))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const W: [F; 6] = [1., 4., 0., 2.5, 0.5, 2.];
+
+    fn frequencies(t: &AliasTable) -> Vec<F> {
+        const N: I = 100_000;
+        let mut c = vec![0.; t.count()];
+        for k in 0..N {
+            let (i, pmf) = t.sample((F::of(k) + 0.5) / F::of(N));
+            assert!((pmf - t.pmf(i)).abs() < F::EPS);
+            c[i] += F::of(N).inv();
+        }
+        c
+    }
+
+    #[test] fn pmf() {
+        let t = AliasTable::new(&W);
+        assert!((t.pmf(1) - 0.4).abs() < F::EPS && t.pmf(2).abs() < F::EPS);
+        let f = frequencies(&t);
+        assert!((0..W.len()).all(|i| (f[i] - t.pmf(i)).abs() < 1e-3));
+        let u = AliasTable::new(&[0., 0., 0., 0.]);
+        assert!((frequencies(&u)[3] - 0.25).abs() < 1e-3);
+    }
+
+    #[test] fn remapped() {
+        let t = AliasTable::new(&W);
+        for k in 0..1000 {
+            let (i, _, r) = t.sample_remapped((F::of(k) + 0.5) / 1000.);
+            assert!(i != 2 && (0. ..1.).contains(&r));
+        }
+        // the remapped samples of an index are uniform
+        let (mut n, mut mean) = (0., 0.);
+        for k in 0..10_000 {
+            let (i, _, r) = t.sample_remapped((F::of(k) + 0.5) / 10_000.);
+            if i == 3 { n += 1.; mean += r; }
+        }
+        assert!((mean / n - 0.5).abs() < 1e-2);
+        assert!(t.sample(1.).0 < W.len());
+    }
+
+    #[cfg(feature="serde-derive")]
+    #[test] fn deser() {
+        let t = serde_json::from_str::<AliasTable>("[1, 4, 0, 2.5, 0.5, 2]").unwrap();
+        assert_eq!(t, AliasTable::new(&W));
+        assert!(serde_json::from_str::<AliasTable>("[]").is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use std::ops::*;
+
+use super::*;
+use crate::{
+    cw_unary_op, cw_binary_op, cw_binary_assign_op,
+    scalar_binary_op, scalar_binary_assign_op
+};
+
+pub type F4 = A4<F>;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+#[repr(C)]
+pub struct A4<A>(pub A, pub A, pub A, pub A);
+
+impl<A> A4<A> {
+    #[inline(always)] pub fn map<B>(self, f: impl Fn(A) -> B) -> A4<B>
+    { A4(f(self.0), f(self.1), f(self.2), f(self.3)) }
+
+    #[inline(always)] pub fn zip<B, C>(self, b: A4<B>, f: impl Fn(A, B) -> C) -> A4<C>
+    { A4(f(self.0, b.0), f(self.1, b.1), f(self.2, b.2), f(self.3, b.3)) }
+
+    #[inline(always)] pub fn zips<B, C>(self, b: B, f: impl Fn(A, B) -> C) -> A4<C> where B: Copy
+    { A4(f(self.0, b), f(self.1, b), f(self.2, b), f(self.3, b)) }
+
+    #[inline(always)] pub fn zipi<B>(&mut self, b: A4<B>, f: impl Fn(&mut A, B))
+    { f(&mut self.0, b.0); f(&mut self.1, b.1); f(&mut self.2, b.2); f(&mut self.3, b.3); }
+
+    #[inline(always)] pub fn zipsi<B>(&mut self, b: B, f: impl Fn(&mut A, B)) where B: Copy
+    { f(&mut self.0, b); f(&mut self.1, b); f(&mut self.2, b); f(&mut self.3, b); }
+
+    #[inline(always)] pub fn fold<B>(self, b: B, f: impl Fn(B, A) -> B) -> B
+    { f(f(f(f(b, self.0), self.1), self.2), self.3) }
+
+    #[inline(always)] pub fn reduce(self, f: impl Fn(A, A) -> A) -> A
+    { f(f(f(self.0, self.1), self.2), self.3) }
+
+    #[inline(always)] pub fn dot<B, C>(a: A4<A>, b: A4<B>) -> C
+        where C: Zero + Add<Output = C>,
+              A4<A>: Mul<A4<B>, Output = A4<C>>,
+    { (a * b).sum() }
+}
+
+impl<A> A4<A> where A: Copy {
+    #[inline(always)] pub const fn rep(a: A) -> A4<A> { A4(a, a, a, a) }
+
+    #[inline(always)] pub const fn a3a(a3: A3<A>, a: A) -> A4<A> { A4(a3.0, a3.1, a3.2, a) }
+
+    #[inline(always)] pub const fn xyz(self) -> A3<A> { A3(self.0, self.1, self.2) }
+}
+
+impl<A> A4<A> where A: Add<Output = A>
+{ #[inline(always)] pub fn sum(self) -> A { self.reduce(Add::add) } }
+
+impl<A> A4<A> where A: Mul<Output = A>
+{ #[inline(always)] pub fn product(self) -> A { self.reduce(Mul::mul) } }
+
+impl<A> Zero for A4<A> where A: Copy + Zero
+{ const ZERO: Self = A4::rep(A::ZERO); }
+
+impl<A> One for A4<A> where A: Copy + One
+{ const ONE: Self = A4::rep(A::ONE); }
+
+macro_rules! index {
+    ($type:ident[$v1:tt, $v2:tt, $v3:tt, $v4:tt]) => {
+        impl<A> Index<$type> for A4<A> {
+            type Output = A;
+            #[inline(always)]
+            fn index(&self, i: $type) -> &Self::Output {
+                match i {
+                    $v1 => &self.0,
+                    $v2 => &self.1,
+                    $v3 => &self.2,
+                    $v4 => &self.3,
+                    _ => unreachable!(),
+                }
+            }
+        }
+
+        impl<A> IndexMut<$type> for A4<A> {
+            #[inline(always)]
+            fn index_mut(&mut self, i: $type) -> &mut Self::Output {
+                match i {
+                    $v1 => &mut self.0,
+                    $v2 => &mut self.1,
+                    $v3 => &mut self.2,
+                    $v4 => &mut self.3,
+                    _ => unreachable!(),
+                }
+            }
+        }
+    };
+}
+
+index!(i32[0, 1, 2, 3]);
+index!(u32[0, 1, 2, 3]);
+index!(usize[0, 1, 2, 3]);
+
+cw_unary_op!(A4, Neg::neg);
+cw_unary_op!(A4, Inv::inv);
+
+cw_binary_op!(A4, Add::add);
+cw_binary_op!(A4, Sub::sub);
+cw_binary_op!(A4, Mul::mul);
+cw_binary_op!(A4, Div::div);
+
+cw_binary_assign_op!(A4, AddAssign::add_assign);
+cw_binary_assign_op!(A4, SubAssign::sub_assign);
+cw_binary_assign_op!(A4, MulAssign::mul_assign);
+cw_binary_assign_op!(A4, DivAssign::div_assign);
+
+scalar_binary_op!(A4, Add::add);
+scalar_binary_op!(A4, Sub::sub);
+scalar_binary_op!(A4, Mul::mul);
+scalar_binary_op!(A4, Div::div);
+
+scalar_binary_assign_op!(A4, AddAssign::add_assign);
+scalar_binary_assign_op!(A4, SubAssign::sub_assign);
+scalar_binary_assign_op!(A4, MulAssign::mul_assign);
+scalar_binary_assign_op!(A4, DivAssign::div_assign);
+
+impl<A> ConvFrom<[A; 4]> for A4<A> where A: Copy
+{ #[inline(always)] fn of(aa: [A; 4]) -> Self { A4(aa[0], aa[1], aa[2], aa[3]) } }
+
+impl<A> ConvFrom<A4<A>> for [A; 4]
+{ #[inline(always)] fn of(aa: A4<A>) -> Self { [aa.0, aa.1, aa.2, aa.3] } }
+
+impl<A, B> Conv<A4<B>> for A4<A> where A: Conv<B> {
+    #[inline(always)] fn conv(self) -> A4<B>
+    { A4(self.0.conv(), self.1.conv(), self.2.conv(), self.3.conv()) }
+}
@@ -0,0 +1,215 @@
+use std::convert::TryFrom;
+
+use super::*;
+
+// Piecewise-constant density over [0, 1) with one bin per function value;
+// an all-zero function is sampled uniformly
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize))]
+#[cfg_attr(feature="serde-derive", serde(try_from="Vec<F>"))]
+pub struct Distribution1D {
+    pdf:      Vec<F>,
+    cdf:      Vec<F>,
+    integral: F,
+}
+
+// Marginal density over rows and conditional densities within each row of a
+// grid stored row by row, for sampling images
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize))]
+#[cfg_attr(feature="serde-derive", serde(try_from="Distribution2DConfig"))]
+pub struct Distribution2D {
+    conditional: Vec<Distribution1D>,
+    marginal:    Distribution1D,
+}
+
+impl Distribution1D {
+    /// # Panics
+    /// If `f` is empty
+    pub fn new(f: &[F]) -> Self {
+        assert!(!f.is_empty(), "distribution over no bins");
+        let n = F::of(f.len() as I);
+        let mut cdf = Vec::with_capacity(f.len() + 1);
+        cdf.push(0.);
+        for &v in f { cdf.push(cdf[cdf.len() - 1] + v.abs() / n); }
+        let integral = cdf[f.len()];
+        let pdf = if integral > 0. {
+            for c in &mut cdf { *c /= integral; }
+            f.iter().map(|v| v.abs() / integral).collect()
+        } else {
+            cdf.iter_mut().enumerate().for_each(|(i, c)| *c = F::of(i as I) / n);
+            vec![1.; f.len()]
+        };
+        Self { pdf, cdf, integral }
+    }
+
+    #[inline(always)] pub const fn count(&self) -> usize { self.pdf.len() }
+    #[inline(always)] pub const fn integral(&self) -> F { self.integral }
+
+    // Bin containing `s` in the cdf and the position of `s` within it
+    #[inline] fn find(&self, s: F) -> (usize, F) {
+        let i = (self.cdf.partition_point(|&c| c <= s).max(1) - 1).min(self.count() - 1);
+        let (c0, c1) = (self.cdf[i], self.cdf[i + 1]);
+        (i, if c1 > c0 { ((s - c0) / (c1 - c0)).clamp(0., F::ONE_MINUS_EPS) } else { 0. })
+    }
+
+    // Position in [0, 1), its density and its bin
+    #[inline] pub fn sample(&self, s: F) -> (F, F, usize) {
+        let (i, t) = self.find(s);
+        (((F::of(i as I) + t) / F::of(self.count() as I)).min(F::ONE_MINUS_EPS), self.pdf[i], i)
+    }
+
+    // Bin, its probability and the sample remapped to [0, 1) within the bin
+    #[inline] pub fn sample_discrete(&self, s: F) -> (usize, F, F) {
+        let (i, t) = self.find(s);
+        (i, self.pmf(i), t)
+    }
+
+    #[inline] pub fn pdf(&self, x: F) -> F {
+        if !(0. ..1.).contains(&x) { return 0. }
+        self.pdf[F::discrete(x, self.count() as I) as usize]
+    }
+
+    #[inline(always)] pub fn pmf(&self, i: usize) -> F { self.cdf[i + 1] - self.cdf[i] }
+
+    // Sample mapping to `x`
+    #[inline] pub fn inverse(&self, x: F) -> F {
+        let n = F::of(self.count() as I);
+        let i = F::discrete(x.clamp(0., 1.), self.count() as I) as usize;
+        LinearScale::interp(A2(self.cdf[i], self.cdf[i + 1]), x.mul_add(n, -F::of(i as I)))
+    }
+}
+
+impl Distribution2D {
+    /// # Panics
+    /// If `f` is empty or its length is not `res.0 * res.1`
+    pub fn new(f: &[F], res: A2<usize>) -> Self {
+        assert_eq!(f.len(), res.0 * res.1, "grid size does not match resolution");
+        let conditional: Vec<_> = f.chunks(res.0).map(Distribution1D::new).collect();
+        let marginal = Distribution1D::new(&conditional.iter().map(Distribution1D::integral)
+                                                       .collect::<Vec<_>>());
+        Self { conditional, marginal }
+    }
+
+    #[inline(always)] pub const fn integral(&self) -> F { self.marginal.integral() }
+
+    // Position in [0, 1)^2 and its density
+    #[inline] pub fn sample(&self, s: F2) -> (F2, F) {
+        let (v, pv, row) = self.marginal.sample(s[Y]);
+        let (u, pu, _) = self.conditional[row].sample(s[X]);
+        (A2(u, v), pu * pv)
+    }
+
+    #[inline] pub fn pdf(&self, p: F2) -> F {
+        if !(0. ..1.).contains(&p[Y]) { return 0. }
+        let row = F::discrete(p[Y], self.marginal.count() as I) as usize;
+        self.marginal.pdf(p[Y]) * self.conditional[row].pdf(p[X])
+    }
+
+    #[inline] pub fn inverse(&self, p: F2) -> F2 {
+        let row = F::discrete(p[Y].clamp(0., 1.), self.marginal.count() as I) as usize;
+        A2(self.conditional[row].inverse(p[X]), self.marginal.inverse(p[Y]))
+    }
+}
+
+impl TryFrom<Vec<F>> for Distribution1D {
+    type Error = &'static str;
+    #[inline] fn try_from(f: Vec<F>) -> Result<Self, Self::Error> {
+        if f.is_empty() { return Err("distribution over no bins") }
+        Ok(Self::new(&f))
+    }
+}
+
+
+#[cfg(feature="serde-derive")]
+#[derive(Debug, Deserialize)]
+struct Distribution2DConfig {
+    f:   Vec<F>,
+    res: A2<usize>,
+}
+
+#[cfg(feature="serde-derive")]
+impl TryFrom<Distribution2DConfig> for Distribution2D {
+    type Error = &'static str;
+    fn try_from(dc: Distribution2DConfig) -> Result<Self, Self::Error> {
+        if dc.f.is_empty() { return Err("distribution over no bins") }
+        if dc.f.len() != dc.res.0 * dc.res.1 { return Err("grid size does not match resolution") }
+        Ok(Self::new(&dc.f, dc.res))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const F1: [F; 5] = [1., 3., 0., 4., 2.];
+
+    #[test] fn distribution_1d() {
+        let d = Distribution1D::new(&F1);
+        assert!((d.integral() - 2.).abs() < F::EPS);
+        assert!((d.pdf(0.1) - 0.5).abs() < F::EPS && (d.pdf(0.7) - 2.).abs() < F::EPS);
+        assert!(d.pdf(0.5).abs() < F::EPS && d.pdf(1.).abs() < F::EPS);
+        let (x, pdf, i) = d.sample(0.25);
+        assert!((x - 0.3).abs() < F::EPS && (pdf - 1.5).abs() < F::EPS && i == 1);
+        assert!((d.inverse(x) - 0.25).abs() < F::EPS);
+        assert_eq!(d.sample(0.45).2, 3);
+        assert!(d.sample(1.).0 < 1.);
+        for k in 0..100 {
+            let s = (F::of(k) + 0.5) / 100.;
+            let (x, pdf, _) = d.sample(s);
+            assert!(pdf > 0. && (pdf - d.pdf(x)).abs() < F::EPS);
+            assert!((d.inverse(x) - s).abs() < F::EPS);
+        }
+    }
+
+    #[test] fn discrete() {
+        let d = Distribution1D::new(&F1);
+        assert!(((0..5).map(|i| d.pmf(i)).sum::<F>() - 1.).abs() < F::EPS);
+        let (i, pmf, r) = d.sample_discrete(0.25);
+        assert!(i == 1 && (pmf - 0.3).abs() < F::EPS && (r - 0.5).abs() < F::EPS);
+        assert_eq!(d.sample_discrete(0.4).0, 3);
+    }
+
+    #[test] fn uniform_fallback() {
+        let d = Distribution1D::new(&[0., 0., 0., 0.]);
+        assert!(d.integral().abs() < F::EPS);
+        let (x, pdf, i) = d.sample(0.6);
+        assert!((x - 0.6).abs() < F::EPS && (pdf - 1.).abs() < F::EPS && i == 2);
+        assert!((d.pmf(3) - 0.25).abs() < F::EPS);
+    }
+
+    #[test] fn distribution_2d() {
+        const N: I = 64;
+        let f = [1., 2., 0., 0., 4., 1., 3., 0., 1.];
+        let d = Distribution2D::new(&f, A2(3, 3));
+        assert!((d.integral() - 4. / 3.).abs() < F::EPS);
+        assert!((d.pdf(A2(0.5, 0.5)) - 3.).abs() < F::EPS);
+        assert!(d.pdf(A2(0.9, 0.1)).abs() < F::EPS);
+        let mut mass = 0.;
+        for i in 0..N {
+            for j in 0..N {
+                let s = A2(F::of(i) + 0.5, F::of(j) + 0.5) / F::of(N);
+                let (p, pdf) = d.sample(s);
+                assert!((pdf - d.pdf(p)).abs() < F::EPS);
+                assert!((d.inverse(p) - s).map(F::abs).max() < 1e-3);
+                mass += pdf.inv();
+            }
+        }
+        assert!((mass / F::of(N * N) - 2. / 3.).abs() < 1e-3);
+    }
+
+    #[cfg(feature="serde-derive")]
+    #[test] fn deser() {
+        let d = serde_json::from_str::<Distribution1D>("[1, 3, 0, 4, 2]").unwrap();
+        assert_eq!(d, Distribution1D::new(&F1));
+        assert!(serde_json::from_str::<Distribution1D>("[]").is_err());
+        let s = r#"{ "f": [1, 2, 0, 0, 4, 1], "res": [3, 2] }"#;
+        assert_eq!(serde_json::from_str::<Distribution2D>(s).unwrap(),
+                   Distribution2D::new(&[1., 2., 0., 0., 4., 1.], A2(3, 2)));
+        assert!(serde_json::from_str::<Distribution2D>(r#"{ "f": [1, 2], "res": [3, 2] }"#)
+                .is_err());
+        assert!(serde_json::from_str::<Distribution2D>(r#"{ "f": [], "res": [0, 0] }"#)
+                .is_err());
+    }
+}
@@ -30,13 +30,77 @@ impl<A> Interp<A> for SmoothScale
     { LinearScale::interp(a, t.sq() * (3. - 2. * t)) }
 }
 
+// Multiple importance sampling heuristics, weighting the first of two
+// strategies or the `i`-th of several by their densities (each scaled by its
+// number of samples); by default several strategies weigh `i` against the
+// rest combined, which is exact for the balance heuristic
 pub trait Balance {
     fn balance(a: F2) -> F;
+
     fn balance2(a: F, b: F) -> F { Self::balance(A2(a, b)) }
+
+    fn balance_n(a: &[F], i: usize) -> F {
+        if a[i] > 0. { Self::balance(A2(a[i], rest(a.iter().copied(), i))) } else { 0. }
+    }
+
+    // One-sample MIS: the factor `w_i / (c_i p_i)` for a sample of strategy
+    // `i` chosen with probability `c[i]`, where `a` holds the densities
+    fn one_sample(c: &[F], a: &[F], i: usize) -> F {
+        let ca = c[i] * a[i];
+        if ca > 0. { Self::balance(A2(ca, rest(c.iter().zip(a).map(|(c, a)| c * a), i))) / ca }
+        else { 0. }
+    }
+}
+
+// Sum of all but the `i`-th term
+#[inline(always)] fn rest(a: impl Iterator<Item=F>, i: usize) -> F
+{ a.enumerate().filter(|&(j, _)| j != i).map(|(_, a)| a).sum() }
+
+impl Balance for LinearScale {
+    #[inline(always)] fn balance(a: F2) -> F { a[0] / a.sum() }
+}
+
+impl Balance for PowerScale {
+    #[inline(always)] fn balance(a: F2) -> F { LinearScale::balance(a.map(F::sq)) }
+
+    #[inline] fn balance_n(a: &[F], i: usize) -> F {
+        let sum = a.iter().map(|a| a.sq()).sum::<F>();
+        if sum > 0. { a[i].sq() / sum } else { 0. }
+    }
+
+    #[inline] fn one_sample(c: &[F], a: &[F], i: usize) -> F {
+        let ca = c[i] * a[i];
+        let sum = c.iter().zip(a).map(|(c, a)| (c * a).sq()).sum::<F>();
+        if ca > 0. { ca / sum } else { 0. }
+    }
 }
 
-impl Balance for LinearScale
-{ #[inline(always)] fn balance(a: F2) -> F { a[0] / a.sum() } }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    const A: [F; 3] = [0.5, 2., 1.5];
+    const C: [F; 3] = [0.2, 0.3, 0.5];
 
-impl Balance for PowerScale
-{ #[inline(always)] fn balance(a: F2) -> F { LinearScale::balance(a.map(F::sq)) } }
+    #[test] fn balance_n() {
+        assert!(close(LinearScale::balance_n(&A[..2], 0), LinearScale::balance2(0.5, 2.)));
+        assert!(close(PowerScale::balance_n(&A[..2], 1), PowerScale::balance2(2., 0.5)));
+        assert!(close((0..3).map(|i| LinearScale::balance_n(&A, i)).sum(), 1.));
+        assert!(close((0..3).map(|i| PowerScale::balance_n(&A, i)).sum(), 1.));
+        assert!(close(LinearScale::balance_n(&[0., 0.], 1), 0.));
+    }
+
+    // Expected weighted estimate of a unit integrand over the strategy choice
+    fn expectation(f: impl Fn(&[F], &[F], usize) -> F) -> F
+    { (0..3).map(|i| C[i] * A[i] * f(&C, &A, i)).sum() }
+
+    #[test] fn one_sample() {
+        assert!(close(expectation(LinearScale::one_sample), 1.));
+        assert!(close(expectation(PowerScale::one_sample), 1.));
+        assert!(close(LinearScale::one_sample(&C, &A, 2), (0.1 + 0.6 + 0.75 as F).inv()));
+        assert!(close(LinearScale::one_sample(&C, &[0., 1., 1.], 0), 0.));
+        let ca = [0.1, 0.6, 0.75];
+        assert!(close(PowerScale::one_sample(&C, &A, 1), PowerScale::balance_n(&ca, 1) / 0.6));
+    }
+}
@@ -1,21 +1,29 @@
+mod alias;
 mod array2;
 mod array3;
+mod array4;
 mod convert;
 mod dim;
+mod distribution;
 mod interpolate;
 mod num;
 mod ops;
+mod pcg32;
 mod scalar;
 
 #[cfg(feature="serde-derive")]
 pub(crate) use serde::{Deserialize, Serialize};
 
+pub use alias::*;
 pub use array2::*;
 pub use array3::*;
+pub use array4::*;
 pub use convert::*;
 pub use dim::*;
+pub use distribution::*;
 pub use interpolate::*;
 pub use num::*;
+pub use pcg32::*;
 pub use scalar::*;
 
 pub type I = i32;
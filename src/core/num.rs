@@ -34,6 +34,8 @@ pub trait Float: Num + Half + OneThird + Inv + Epsilon {
     const INV_4PI: Self;
 
     const FRAC_1_2POW32: Self;
+    const MACH_EPS: Self;
+    const ONE_MINUS_EPS: Self;
 
     fn ceili(self) -> I;
     fn floori(self) -> I;
@@ -48,6 +50,11 @@ pub trait Float: Num + Half + OneThird + Inv + Epsilon {
     fn discrete(a: Self, n: I) -> I;
 }
 
+#[inline(always)] pub fn gamma(n: I) -> F {
+    let n = F::of(n) * F::MACH_EPS;
+    n / (1. - n)
+}
+
 #[inline] pub fn difference_of_products(a: F, b: F, c: F, d: F) -> F {
     let cd = c * d;
     a.mul_add(b, -cd) + c.mul_add(-d, cd)
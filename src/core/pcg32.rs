@@ -0,0 +1,116 @@
+use super::*;
+
+const MULT: u64 = 0x5851_f42d_4c95_7f2d;
+const DEFAULT_STATE: u64 = 0x853c_49e6_748f_ea9b;
+const DEFAULT_STREAM: u64 = 0xda3e_39cb_94b9_5bdb;
+
+// O'Neill's PCG32 (XSH-RR output over a 64-bit LCG), with 2^63 selectable
+// streams; only integer arithmetic, so sequences are the same on every platform
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct Pcg32 {
+    state: u64,
+    inc:   u64,
+}
+
+impl Default for Pcg32
+{ #[inline(always)] fn default() -> Self { Self { state: DEFAULT_STATE, inc: DEFAULT_STREAM } } }
+
+impl Pcg32 {
+    #[inline] pub const fn new(seed: u64, stream: u64) -> Self {
+        let mut rng = Self { state: 0, inc: (stream << 1) | 1 };
+        rng.step();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.step();
+        rng
+    }
+
+    #[inline(always)] const fn step(&mut self)
+    { self.state = self.state.wrapping_mul(MULT).wrapping_add(self.inc); }
+
+    #[inline] pub const fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        xorshifted.rotate_right((old >> 59) as u32)
+    }
+
+    // Uniform in [0, 1)
+    #[inline] pub fn next_f(&mut self) -> F
+    { (F::of(self.next_u32()) * F::FRAC_1_2POW32).min(F::ONE_MINUS_EPS) }
+
+    #[inline(always)] pub fn next_f2(&mut self) -> F2 { A2(self.next_f(), self.next_f()) }
+
+    #[inline(always)] pub fn next_f3(&mut self) -> F3
+    { A3(self.next_f(), self.next_f(), self.next_f()) }
+
+    // Jumps `delta` steps ahead (or back, if negative) in logarithmic time
+    // (Brown, "Random Number Generation with Arbitrary Stride")
+    pub const fn advance(&mut self, delta: i64) {
+        let (mut mult, mut plus) = (MULT, self.inc);
+        let (mut acc_mult, mut acc_plus) = (1_u64, 0_u64);
+        let mut delta = delta as u64;
+        while delta > 0 {
+            if delta & 1 == 1 {
+                acc_mult = acc_mult.wrapping_mul(mult);
+                acc_plus = acc_plus.wrapping_mul(mult).wrapping_add(plus);
+            }
+            plus = mult.wrapping_add(1).wrapping_mul(plus);
+            mult = mult.wrapping_mul(mult);
+            delta >>= 1;
+        }
+        self.state = acc_mult.wrapping_mul(self.state).wrapping_add(acc_plus);
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test] fn reference_sequence() {
+        // from the reference implementation's pcg32-demo
+        let mut rng = Pcg32::new(42, 54);
+        let out: Vec<_> = (0..6).map(|_| rng.next_u32()).collect();
+        assert_eq!(out, [0xa15c_02b7, 0x7b47_f409, 0xba1d_3330,
+                         0x83d2_f293, 0xbfa4_784b, 0xcbed_606e]);
+    }
+
+    #[test] fn streams() {
+        let (mut a, mut b) = (Pcg32::new(7, 1), Pcg32::new(7, 2));
+        assert!((0..8).any(|_| a.next_u32() != b.next_u32()));
+        assert_ne!(Pcg32::default().next_u32(), Pcg32::new(0, 0).next_u32());
+    }
+
+    #[test] fn advance() {
+        let mut a = Pcg32::new(3, 5);
+        let mut b = a;
+        for _ in 0..1000 { a.next_u32(); }
+        b.advance(1000);
+        assert_eq!(a, b);
+        b.advance(-1000);
+        assert_eq!(b, Pcg32::new(3, 5));
+    }
+
+    #[test] fn floats() {
+        let mut rng = Pcg32::new(11, 0);
+        let mean = (0..10_000).map(|_| {
+            let f = rng.next_f();
+            assert!((0. ..1.).contains(&f));
+            f
+        }).sum::<F>() / 10_000.;
+        assert!((mean - 0.5).abs() < 1e-2);
+        let (mut a, mut b) = (Pcg32::new(11, 0), Pcg32::new(11, 0));
+        assert_eq!(a.next_f3(), A3(b.next_f(), b.next_f(), b.next_f()));
+        assert_eq!(a.next_f2(), A2(b.next_f(), b.next_f()));
+    }
+
+    #[cfg(feature="serde-derive")]
+    #[test] fn serde() {
+        let mut rng = Pcg32::new(1, 2);
+        rng.next_u32();
+        let s = serde_json::to_string(&rng).unwrap();
+        let mut de = serde_json::from_str::<Pcg32>(&s).unwrap();
+        assert_eq!(de.next_u32(), rng.next_u32());
+    }
+}
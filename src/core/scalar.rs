@@ -32,6 +32,8 @@ impl Float for F {
     const INV_4PI: F = F::HALF * F::INV_2PI;
 
     const FRAC_1_2POW32: F = 2.328_306_4e-10;
+    const MACH_EPS: F = F::EPSILON * 0.5;
+    const ONE_MINUS_EPS: F = F::ONE - F::MACH_EPS;
 
     #[inline(always)] fn ceili(self) -> I { self.ceil().conv() }
     #[inline(always)] fn floori(self) -> I { self.floor().conv() }
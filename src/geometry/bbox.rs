@@ -10,6 +10,10 @@ pub struct BBox(pub A3<B>);
 
 impl Zero for BBox { const ZERO: Self = BBox(A3::ZERO); }
 
+pub trait Bounded { fn bbox(&self) -> BBox; }
+
+impl Bounded for BBox { #[inline(always)] fn bbox(&self) -> BBox { *self } }
+
 impl BBox {
     #[inline(always)] pub fn center(&self) -> P { P(self.0.map(B::center)) }
     #[inline(always)] pub fn extents(&self) -> F3 { self.0.map(B::extent) }
@@ -21,10 +25,64 @@ impl BBox {
         (c, (self.upper() - c).norm())
     }
 
+    #[inline(always)] pub fn surface_area(&self) -> F {
+        let e = self.extents();
+        2. * e[X].mul_add(e[Y], e[Y].mul_add(e[Z], e[Z] * e[X]))
+    }
+
+    #[inline(always)] pub fn volume(&self) -> F { self.extents().product() }
+
+    #[inline(always)] pub fn corner(&self, i: usize) -> P
+    { P(A3(self[X][i & 1], self[Y][(i >> 1) & 1], self[Z][(i >> 2) & 1])) }
+
+    #[inline(always)] pub fn corners(&self) -> [P; 8]
+    { [0, 1, 2, 3, 4, 5, 6, 7].map(|i| self.corner(i)) }
+
+    #[inline(always)] pub fn contains(&self, p: P) -> bool
+    { self.0.zip(p.0, B::bounds).fold(true, |a, b| a && b) }
+
+    #[inline(always)] pub fn overlaps(&self, b: &BBox) -> bool
+    { !(*self & *b).0.map(B::degen).fold(false, |a, b| a || b) }
+
+    // Position of `p` relative to the box, with the lower corner at 0 and the
+    // upper at 1
+    #[inline(always)] pub fn offset(&self, p: P) -> F3 {
+        (p - self.lower()).0.zip(self.extents(), |o, e| if e > 0. { o / e } else { o })
+    }
+
+    #[inline(always)] pub fn lerp(&self, t: F3) -> P
+    { P(self.0.zip(t, |b, t| LinearScale::interp(b.0, t))) }
+
+    #[must_use]
+    #[inline(always)] pub fn expand(&self, d: F) -> BBox
+    { BBox(self.0.map(|b| B::b(b.lower() - d, b.upper() + d))) }
+
+    #[inline(always)] pub fn dist2(&self, p: P) -> F {
+        self.0.zip(p.0, |b, p| F::max(F::max(b.lower() - p, p - b.upper()), 0.).sq())
+              .sum()
+    }
+
     #[inline] pub fn max_extent(&self) -> (F, Dim) {
         self.extents().zip(XYZ, |a, b| (a, b))
             .reduce(|(a, b), (c, d)| if a > c { (a, b) } else { (c, d) })
     }
+
+    #[inline(always)] pub fn intersect(&self, r: R) -> Option<B>
+    { self.intersect_inv(&r.conv()) }
+
+    // Slab test, with comparisons ordered so that NaNs from 0 * inf are ignored
+    #[inline] pub fn intersect_inv(&self, r: &RInv) -> Option<B> {
+        let scale = gamma(3).mul_add(2., 1.);
+        XYZ.fold(Some(r.range()), |t, dim| t.and_then(|t| {
+            let b = self[dim].0;
+            let l = (b[r.neg[dim]] - r.o[dim]) * r.d[dim];
+            let u = (b[!r.neg[dim]] - r.o[dim]) * r.d[dim] * scale;
+            if t.lower() > u || l > t.upper() { return None }
+            let l = if l > t.lower() { l } else { t.lower() };
+            let u = if u < t.upper() { u } else { t.upper() };
+            Some(B::b(l, u))
+        }))
+    }
 }
 
 op!(Add::add, *BBox -> *P -> BBox);
@@ -45,11 +103,79 @@ impl Index<Dim> for BBox {
 }
 
 
-#[cfg(feature="serde-derive")]
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn unit_box() -> BBox { BBox(A3::rep(B::b(-1., 1.))) }
+
+    #[test] fn intersect_hit() {
+        let r = R::unbounded(P(A3(-5., 0.5, 0.)), V(A3(1., 0., 0.)));
+        let t = unit_box().intersect(r).unwrap();
+        assert!((t.lower() - 4.).abs() < F::EPS);
+        assert!((t.upper() - 6.).abs() < F::EPS);
+    }
+
+    #[test] fn intersect_miss() {
+        let r = R::unbounded(P(A3(-5., 2., 0.)), V(A3(1., 0., 0.)));
+        assert_eq!(unit_box().intersect(r), None);
+    }
+
+    #[test] fn intersect_clipped() {
+        let r = R::r(P(A3(-5., 0., 0.)), V(A3(1., 0., 0.)), 3.);
+        assert_eq!(unit_box().intersect(r), None);
+    }
+
+    #[test] fn intersect_inside() {
+        let r = R::unbounded(P::ZERO, V(A3(0., 1., 1.)));
+        let t = unit_box().intersect(r).unwrap();
+        assert!((t.lower() - F::EPS).abs() < F::EPS);
+        assert!((t.upper() - F::sqrt(2.)).abs() < F::EPS);
+    }
+
+    #[test] fn measures() {
+        let b = BBox(A3(B::b(0., 1.), B::b(0., 2.), B::b(0., 3.)));
+        assert!((b.surface_area() - 22.).abs() < F::EPS);
+        assert!((b.volume() - 6.).abs() < F::EPS);
+    }
+
+    #[test] fn corners() {
+        let b = BBox(A3(B::b(0., 1.), B::b(0., 2.), B::b(0., 3.)));
+        let c = b.corners();
+        assert_eq!(c[0], b.lower());
+        assert_eq!(c[7], b.upper());
+        assert_eq!(c[5], P(A3(1., 0., 3.)));
+        assert_eq!(c.iter().fold(BBox::ZERO, |b, &p| b | p), b);
+    }
+
+    #[test] fn containment() {
+        let b = unit_box();
+        assert!(b.contains(P(A3(1., 0., -1.))));
+        assert!(!b.contains(P(A3(1.5, 0., 0.))));
+        assert!(b.overlaps(&(b + P(A3(2., 0., 0.)))));
+        assert!(!b.overlaps(&(b + P(A3(2.5, 0., 0.)))));
+    }
+
+    #[test] fn offset_lerp() {
+        let b = BBox(A3(B::b(-1., 1.), B::b(0., 4.), B::point(2.)));
+        let p = P(A3(0., 1., 2.));
+        assert_eq!(b.offset(p), A3(0.5, 0.25, 0.));
+        assert_eq!(b.lerp(b.offset(p)), p);
+    }
+
+    #[test] fn expand_dist2() {
+        let b = unit_box().expand(1.);
+        assert_eq!(b, BBox(A3::rep(B::b(-2., 2.))));
+        assert!((b.dist2(P(A3(0., 5., -4.))) - 13.).abs() < F::EPS);
+        assert!(b.dist2(P(A3(0., 1., -1.))) < F::EPS);
+    }
+
+    #[test] fn intersect_on_slab_boundary() {
+        let r = R::unbounded(P(A3(-5., 1., 0.)), V(A3(1., 0., 0.)));
+        assert!(unit_box().intersect(r).is_some());
+    }
+
+    #[cfg(feature="serde-derive")]
     #[test] fn deser_bbox() {
         let s = "[[-1, 1], [2, 10], [0.5, -0.5]]";
         assert_eq!(serde_json::from_str::<BBox>(s).unwrap(),
@@ -0,0 +1,229 @@
+use super::*;
+
+const N_BINS: usize = 12;
+const MAX_LEAF: usize = 4;
+const MAX_SAH_DEPTH: usize = 32;
+const STACK_SIZE: usize = 64;
+const TRAVERSAL_COST: F = 0.125;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Node {
+    Leaf { bbox: BBox, start: usize, n: usize },
+    Inner { bbox: BBox, right: usize, dim: Dim },
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bvh {
+    nodes: Vec<Node>,
+    idx:   Vec<usize>,
+}
+
+#[derive(Clone, Copy)]
+struct Item {
+    bbox: BBox,
+    c:    P,
+    i:    usize,
+}
+
+impl Node {
+    #[inline(always)] const fn bbox(&self) -> &BBox {
+        match self { Self::Leaf { bbox, .. } | Self::Inner { bbox, .. } => bbox }
+    }
+}
+
+impl Bvh {
+    pub fn new<A: Bounded>(prims: &[A]) -> Self {
+        let mut items: Vec<_> = prims.iter().enumerate().map(|(i, p)| {
+            let bbox = p.bbox();
+            Item { bbox, c: bbox.center(), i }
+        }).collect();
+        let mut nodes = Vec::with_capacity(2 * items.len());
+        if !items.is_empty() { build(&mut items, 0, 0, &mut nodes); }
+        Self { nodes, idx: items.iter().map(|item| item.i).collect() }
+    }
+
+    #[inline(always)] pub fn bbox(&self) -> BBox
+    { self.nodes.first().map_or(BBox::ZERO, |node| *node.bbox()) }
+
+    // Closest hit; `f` intersects the primitive at the given index against a
+    // ray clipped to the closest hit so far
+    #[inline] pub fn intersect(&self, r: R, mut f: impl FnMut(usize, R) -> Option<Hit>)
+        -> Option<(usize, Hit)>
+    {
+        let mut ri: RInv = r.conv();
+        let mut hit = None;
+        self.traverse(&mut ri, |i, ri| {
+            if let Some(h) = f(i, r.clipped(ri.t)) {
+                ri.t = h.t;
+                hit = Some((i, h));
+            }
+            false
+        });
+        hit
+    }
+
+    // Any hit; traversal stops as soon as `f` reports an intersection
+    #[inline] pub fn intersects(&self, r: R, mut f: impl FnMut(usize, R) -> bool) -> bool
+    { self.traverse(&mut r.conv(), |i, ri| f(i, r.clipped(ri.t))) }
+
+    #[inline] fn traverse(&self, ri: &mut RInv, mut f: impl FnMut(usize, &mut RInv) -> bool)
+        -> bool
+    {
+        if self.nodes.is_empty() { return false }
+        let mut stack = [0; STACK_SIZE];
+        let mut sp = 0;
+        let mut i = 0;
+        loop {
+            let node = &self.nodes[i];
+            if node.bbox().intersect_inv(ri).is_some() {
+                match *node {
+                    Node::Leaf { start, n, .. } => {
+                        for &p in &self.idx[start..start + n] {
+                            if f(p, ri) { return true }
+                        }
+                    }
+                    Node::Inner { right, dim, .. } => {
+                        let (near, far) = if ri.neg[dim] { (right, i + 1) }
+                                          else { (i + 1, right) };
+                        stack[sp] = far;
+                        sp += 1;
+                        i = near;
+                        continue
+                    }
+                }
+            }
+            if sp == 0 { return false }
+            sp -= 1;
+            i = stack[sp];
+        }
+    }
+}
+
+fn build(items: &mut [Item], start: usize, depth: usize, nodes: &mut Vec<Node>) {
+    let bbox = items.iter().fold(BBox::ZERO, |b, item| b | item.bbox);
+    let n = items.len();
+    let cbox = items.iter().fold(BBox::ZERO, |b, item| b | item.c);
+    let (extent, dim) = cbox.max_extent();
+    if n <= MAX_LEAF || extent <= 0. {
+        nodes.push(Node::Leaf { bbox, start, n });
+        return
+    }
+
+    let bin = |item: &Item| F::discrete(cbox.offset(item.c)[dim], N_BINS as I) as usize;
+
+    let split = if depth < MAX_SAH_DEPTH { sah_split(items, &bbox, bin) } else { None };
+    let mid = split.unwrap_or_else(|| {
+        let mid = n / 2;
+        items.select_nth_unstable_by(mid, |a, b| a.c[dim].total_cmp(&b.c[dim]));
+        mid
+    });
+
+    let node = nodes.len();
+    nodes.push(Node::Inner { bbox, right: 0, dim });
+    build(&mut items[..mid], start, depth + 1, nodes);
+    let right = nodes.len();
+    nodes[node] = Node::Inner { bbox, right, dim };
+    build(&mut items[mid..], start + mid, depth + 1, nodes);
+}
+
+// Binned SAH (Wald, "On fast Construction of SAH-based Bounding Volume
+// Hierarchies"); returns the partition point or `None` if all items share a bin
+fn sah_split(items: &mut [Item], bbox: &BBox, bin: impl Fn(&Item) -> usize)
+    -> Option<usize>
+{
+    let mut bins = [(BBox::ZERO, 0); N_BINS];
+    for item in items.iter() {
+        let b = &mut bins[bin(item)];
+        b.0 = b.0 | item.bbox;
+        b.1 += 1;
+    }
+
+    let mut right = [(BBox::ZERO, 0); N_BINS];
+    let mut acc = (BBox::ZERO, 0);
+    for i in (1..N_BINS).rev() {
+        acc = (acc.0 | bins[i].0, acc.1 + bins[i].1);
+        right[i] = acc;
+    }
+
+    let sa = bbox.surface_area().inv();
+    let mut left = (BBox::ZERO, 0);
+    let (split, _) = (0..N_BINS - 1).map(|i| {
+        left = (left.0 | bins[i].0, left.1 + bins[i].1);
+        let (rb, rn) = right[i + 1];
+        let cost = F::of(left.1).mul_add(left.0.surface_area(),
+                                         F::of(rn) * rb.surface_area())
+                                .mul_add(sa, TRAVERSAL_COST);
+        (i, if left.1 == 0 || rn == 0 { F::POS_INF } else { cost })
+    }).fold((0, F::POS_INF), |a, b| if b.1 < a.1 { b } else { a });
+
+    let mut mid = 0;
+    for i in 0..items.len() {
+        if bin(&items[i]) <= split {
+            items.swap(i, mid);
+            mid += 1;
+        }
+    }
+    if mid == 0 || mid == items.len() { None } else { Some(mid) }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spheres() -> Vec<Sphere> {
+        let mut s = 17_u32;
+        let mut rand = || {
+            s ^= s << 13; s ^= s >> 17; s ^= s << 5;
+            F::of(s) * F::FRAC_1_2POW32
+        };
+        (0..500).map(|_| {
+            let c = P(A3(rand(), rand(), rand()) * 20. - 10.);
+            Sphere::new(c, rand().mul_add(0.8, 0.05))
+        }).collect()
+    }
+
+    fn rays() -> impl Iterator<Item=R> {
+        (0..30).flat_map(|i| (0..30).map(move |j| {
+            let d = V(A3(F::of(i) - 14.5, F::of(j) - 14.5, 20.));
+            R::unbounded(P(A3(0.5, -0.25, -25.)), d)
+        }))
+    }
+
+    #[test] fn empty() {
+        let bvh = Bvh::new::<Sphere>(&[]);
+        let r = R::unbounded(P::ZERO, V(A3(0., 0., 1.)));
+        assert_eq!(bvh.intersect(r, |_, _| unreachable!()), None);
+        assert_eq!(bvh.bbox(), BBox::ZERO);
+    }
+
+    #[test] fn bbox() {
+        let s = spheres();
+        let b = s.iter().fold(BBox::ZERO, |b, s| b | s.bbox());
+        assert_eq!(Bvh::new(&s).bbox(), b);
+    }
+
+    #[test] fn closest_hit_matches_brute_force() {
+        let s = spheres();
+        let bvh = Bvh::new(&s);
+        for r in rays() {
+            let brute = s.iter().enumerate()
+                         .filter_map(|(i, s)| s.intersect(r).map(|h| (i, h)))
+                         .fold(None, |a: Option<(usize, Hit)>, b| match a {
+                             Some(a) if a.1.t <= b.1.t => Some(a),
+                             _ => Some(b),
+                         });
+            let hit = bvh.intersect(r, |i, r| s[i].intersect(r));
+            assert_eq!(hit.map(|h| h.0), brute.map(|h| h.0));
+            assert_eq!(bvh.intersects(r, |i, r| s[i].intersects(r)), brute.is_some());
+        }
+    }
+
+    #[test] fn coincident_primitives() {
+        let s = vec![Sphere::new(P::ZERO, 1.); 100];
+        let bvh = Bvh::new(&s);
+        let r = R::unbounded(P(A3(0., 0., -5.)), V(A3(0., 0., 1.)));
+        assert!((bvh.intersect(r, |i, r| s[i].intersect(r)).unwrap().1.t - 4.).abs()
+                < F::EPS);
+    }
+}
@@ -0,0 +1,279 @@
+use super::*;
+
+const MIN_EXPECTED: f64 = 5.;
+const QUAD_EPS: f64 = 1e-6;
+const QUAD_DEPTH: u32 = 10;
+
+// 5-point Gauss-Legendre nodes and weights on [-1, 1]
+const GAUSS: [(f64, f64); 5] = [(-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+                                (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+                                (0., 0.568_888_888_888_888_9),
+                                (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+                                (0.906_179_845_938_664, 0.236_926_885_056_189_1)];
+
+// Example parameters for the warps that take a direction, a spherical
+// triangle or a spherical rectangle (corner and two edges), shared by the
+// tests and warptest
+pub const EXAMPLE_WO: F3 = A3(0.48, -0.6, 0.64);
+pub const EXAMPLE_TRIANGLE: A3<F3> = A3(A3(1., -0.5, 2.), A3(-0.5, 1.5, 1.), A3(-1., -1., 3.));
+pub const EXAMPLE_RECTANGLE: A3<F3> = A3(A3(-0.5, 0.3, -1.), A3(2., 0., 0.), A3(0., 0.4, 1.2));
+
+// Parameterization of a warp's output domain by the unit square: planar
+// outputs cover [-1, 1]^2 and spherical ones (cos(theta), phi)
+pub trait Chart: Copy {
+    // Measure of the domain per unit area of the chart
+    const JACOBIAN: F;
+
+    fn chart(self) -> F2;
+    fn unchart(u: F2) -> Self;
+}
+
+impl Chart for F2 {
+    const JACOBIAN: F = 4.;
+    #[inline(always)] fn chart(self) -> F2 { (self + 1.) * 0.5 }
+    #[inline(always)] fn unchart(u: F2) -> F2 { u * 2. - 1. }
+}
+
+impl Chart for F3 {
+    const JACOBIAN: F = F::FOUR_PI;
+
+    #[inline(always)] fn chart(self) -> F2
+    { A2((self[Z] + 1.) * 0.5, Frame::cart2spher(self)[Y] * F::INV_2PI) }
+
+    #[inline(always)] fn unchart(u: F2) -> F3
+    { Frame::spher2cart(A2(F::acos(u[X].mul_add(2., -1.).clamp(-1., 1.)), F::TWO_PI * u[Y])) }
+}
+
+// Pearson's chi-squared goodness-of-fit of a warp against its pdf over a
+// histogram of its chart (after Mitsuba's warptest)
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChiSquareTest {
+    pub res:      A2<usize>,
+    pub observed: Vec<F>,
+    pub expected: Vec<F>,
+    outside:      usize,
+}
+
+impl ChiSquareTest {
+    pub fn new<W>(w: &W, p: W::Param, res: A2<usize>, samples: impl IntoIterator<Item=F2>)
+        -> Self where W: Warp<Sample=F2>, W::Output: Chart
+    {
+        let mut observed = vec![0.; res.0 * res.1];
+        let mut outside = 0;
+        let mut n = 0;
+        for sample in samples {
+            n += 1;
+            let u = w.warp(sample, p).chart();
+            if !(0. ..=1.).contains(&u[X]) || !(0. ..=1.).contains(&u[Y]) {
+                outside += 1;
+                continue
+            }
+            let bin = A2(u[X] * F::of(res.0 as I), u[Y] * F::of(res.1 as I))
+                    .zip(res, |u, r| (u as usize).min(r - 1));
+            observed[bin.1 * res.0 + bin.0] += 1.;
+        }
+
+        let cell = A2(1. / res.0 as f64, 1. / res.1 as f64);
+        let scale = f64::of(W::Output::JACOBIAN) * f64::of(n);
+        let expected = (0..res.1).flat_map(|j| (0..res.0).map(move |i| (i, j))).map(|(i, j)| {
+            let lower = A2(i as f64 * cell.0, j as f64 * cell.1);
+            let density = |x: f64, y: f64| {
+                f64::of(w.pdf(W::Output::unchart(A2(x as F, y as F)), p))
+            };
+            (integrate2(&density, lower, lower + cell) * scale) as F
+        }).collect();
+
+        Self { res, observed, expected, outside }
+    }
+
+    #[inline] pub fn n_samples(&self) -> F
+    { self.observed.iter().sum::<F>() + F::of(self.outside as I) }
+
+    // Statistic and degrees of freedom, pooling cells with low expected counts
+    pub fn statistic(&self) -> (F, usize) {
+        let mut cells: Vec<_> = self.observed.iter().zip(&self.expected)
+                                    .map(|(&o, &e)| (f64::of(o), f64::of(e))).collect();
+        cells.sort_by(|a, b| a.1.total_cmp(&b.1));
+        let (mut chi2, mut dof) = (0., 0);
+        let mut pool = (0., 0.);
+        for (o, e) in cells {
+            if e < MIN_EXPECTED || (pool.1 > 0. && pool.1 < MIN_EXPECTED) {
+                pool = (pool.0 + o, pool.1 + e);
+            } else {
+                chi2 += (o - e) * (o - e) / e;
+                dof += 1;
+            }
+        }
+        if pool.1 > 0. {
+            chi2 += (pool.0 - pool.1) * (pool.0 - pool.1) / pool.1;
+            dof += 1;
+        }
+        (chi2 as F, dof.max(2) - 1)
+    }
+
+    // Probability of a statistic at least this large if the warp matches its pdf
+    #[inline] pub fn p_value(&self) -> F {
+        let (chi2, dof) = self.statistic();
+        gamma_q(0.5 * dof as f64, 0.5 * f64::of(chi2)) as F
+    }
+
+    /// # Errors
+    /// Fails if samples land outside the chart or in cells with no expected
+    /// mass, or if the fit is rejected at level `alpha` corrected for
+    /// `n_tests` independent tests
+    pub fn run(&self, alpha: F, n_tests: I) -> Result<F, String> {
+        let n = self.n_samples();
+        if self.outside > 0 { return Err(format!("{} samples outside the domain", self.outside)) }
+        let stray = self.observed.iter().zip(&self.expected)
+                        .filter(|&(_, &e)| e == 0.).map(|(&o, _)| o).sum::<F>();
+        if stray > n * 1e-5 { return Err(format!("{stray} samples where the pdf is zero")) }
+        let total = self.expected.iter().sum::<F>() / n;
+        if (total - 1.).abs() > 1e-2 { return Err(format!("pdf integrates to {total}")) }
+        let p = self.p_value();
+        let level = 1. - (1. - alpha).powf(F::of(n_tests).inv());
+        if p < level { Err(format!("rejected with p-value {p} < {level}")) } else { Ok(p) }
+    }
+}
+
+// Nested adaptive Gauss-Legendre quadrature over a rectangle; the rule is
+// open, so a pdf whose support ends on a cell edge is never evaluated there
+fn integrate2(f: &impl Fn(f64, f64) -> f64, lower: A2<f64>, upper: A2<f64>) -> f64
+{ integrate(&|x| integrate(&|y| f(x, y), lower.1, upper.1), lower.0, upper.0) }
+
+#[inline(always)] fn integrate(f: &impl Fn(f64) -> f64, a: f64, b: f64) -> f64
+{ refine(f, A2(a, b), gauss(f, a, b), QUAD_EPS, QUAD_DEPTH) }
+
+fn refine(f: &impl Fn(f64) -> f64, ab: A2<f64>, whole: f64, eps: f64, depth: u32) -> f64 {
+    let A2(lo, hi) = ab;
+    let mid = 0.5 * (lo + hi);
+    let (left, right) = (gauss(f, lo, mid), gauss(f, mid, hi));
+    if depth == 0 || (left + right - whole).abs() <= eps { return left + right }
+    refine(f, A2(lo, mid), left, 0.5 * eps, depth - 1)
+    + refine(f, A2(mid, hi), right, 0.5 * eps, depth - 1)
+}
+
+#[inline(always)] fn gauss(f: &impl Fn(f64) -> f64, a: f64, b: f64) -> f64 {
+    let (mid, half) = (0.5 * (a + b), 0.5 * (b - a));
+    half * GAUSS.iter().map(|&(x, w)| w * f(half.mul_add(x, mid))).sum::<f64>()
+}
+
+// Regularized upper incomplete gamma function (Numerical Recipes, 6.2)
+#[allow(clippy::many_single_char_names)]
+fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0. { return 1. }
+    let norm = (a.mul_add(x.ln(), -x) - ln_gamma(a)).exp();
+    if x < a + 1. {
+        let (mut ap, mut del) = (a, a.recip());
+        let mut sum = del;
+        loop {
+            ap += 1.;
+            del *= x / ap;
+            sum += del;
+            if del.abs() < sum.abs() * 1e-15 { break }
+        }
+        sum.mul_add(-norm, 1.)
+    } else {
+        let tiny = f64::MIN_POSITIVE / f64::EPSILON;
+        let mut b = x + 1. - a;
+        let (mut c, mut d) = (tiny.recip(), b.recip());
+        let mut h = d;
+        for i in 1..1000 {
+            let an = -f64::from(i) * (f64::from(i) - a);
+            b += 2.;
+            d = an.mul_add(d, b);
+            if d.abs() < tiny { d = tiny; }
+            c = b + an / c;
+            if c.abs() < tiny { c = tiny; }
+            d = d.recip();
+            h *= d * c;
+            if d.mul_add(c, -1.).abs() < 1e-15 { break }
+        }
+        h * norm
+    }
+}
+
+// Lanczos approximation (g = 7) for a >= 0.5
+#[allow(clippy::excessive_precision, clippy::unreadable_literal)]
+fn ln_gamma(a: f64) -> f64 {
+    const C: [f64; 9] = [0.99999999999980993, 676.5203681218851, -1259.1392167224028,
+                         771.32342877765313, -176.61502916214059, 12.507343278686905,
+                         -0.13857109526572012, 9.9843695780195716e-6, 1.5056327351493116e-7];
+    let x = a - 1.;
+    let sum = (1..9).fold(C[0], |s, i| s + C[i] / (x + i as f64));
+    let t = x + 7.5;
+    (x + 0.5).mul_add(t.ln(), 0.5 * (2. * std::f64::consts::PI).ln()) - t + sum.ln()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const N_TESTS: I = 19;
+
+    fn samples(n: usize) -> impl Iterator<Item=F2> {
+        let mut rng = Pcg32::new(0x2545_f491_4f6c_dd1d, 0);
+        (0..n).map(move |_| rng.next_f2())
+    }
+
+    fn check<W>(w: &W, p: W::Param) where W: Warp<Sample=F2>, W::Output: Chart {
+        let t = ChiSquareTest::new(w, p, A2(24, 48), samples(200_000));
+        t.run(0.01, N_TESTS).unwrap();
+    }
+
+    #[test] fn gamma() {
+        assert!((ln_gamma(5.) - 24_f64.ln()).abs() < 1e-12);
+        assert!((gamma_q(1., 2.) - (-2_f64).exp()).abs() < 1e-12);
+        assert!((gamma_q(2.5, 1.) - 0.849_145_036_084_752).abs() < 1e-9);
+        assert!((gamma_q(40., 60.) - 0.002_548_192_303_613).abs() < 1e-9);
+    }
+
+    #[test] fn planar() {
+        check(&UniformTriangle, ());
+        check(&UniformDisk, ());
+        check(&Bilinear, A4(1., 0.2, 3., 0.5));
+    }
+
+    #[test] fn spherical() {
+        check(&UniformSphere, ());
+        check(&UniformHemisphere, ());
+        check(&UniformCylinder, ());
+        check(&UniformCone, 0.6);
+        check(&CosineHemisphere, ());
+        check(&IsotropicPhase, ());
+    }
+
+    #[test] fn spherical_polygons() {
+        check(&SphericalTriangle, EXAMPLE_TRIANGLE);
+        check(&SphericalRectangle, EXAMPLE_RECTANGLE);
+    }
+
+    #[test] fn microfacet() {
+        check(&BeckmannHemisphere, 0.4);
+        check(&TrowbridgeReitzHemisphere, 0.4);
+        let wo = Frame::spher2cart(A2(1., 0.4));
+        check(&TrowbridgeReitzVisible, (wo, A2(0.3, 0.6)));
+        check(&TrowbridgeReitzVisibleCap, (wo, A2(0.3, 0.6)));
+    }
+
+    #[test] fn phase() {
+        check(&HenyeyGreenstein, (0.5, EXAMPLE_WO));
+        check(&HenyeyGreenstein, (-0.3, EXAMPLE_WO));
+        check(&TwoLobeHenyeyGreenstein, (A2(0.6, -0.4), 0.3, EXAMPLE_WO));
+        check(&Rayleigh, EXAMPLE_WO);
+    }
+
+    #[test] fn support_on_cell_edges() {
+        // the hemisphere ends exactly on the cell edge at the equator
+        let t = ChiSquareTest::new(&UniformHemisphere, (), A2(24, 48), samples(1000));
+        let below = (0..48).flat_map(|j| (0..12).map(move |i| j * 24 + i));
+        assert!(below.map(|k| t.expected[k]).all(|e| e == 0.));
+        assert!((t.expected.iter().sum::<F>() - 1000.).abs() < 1e-2);
+    }
+
+    #[test] fn detects_mismatch() {
+        let t = ChiSquareTest::new(&UniformSphere, (), A2(24, 48),
+                                   samples(200_000).map(|s| A2(s[X].sqrt(), s[Y])));
+        assert!(t.run(0.01, 1).is_err());
+    }
+}
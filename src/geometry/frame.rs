@@ -1,9 +1,19 @@
 use super::*;
+use crate::conv;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
 pub struct Frame;
 
+// Orthonormal basis with `n` as the local z-axis
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct ShadingFrame {
+    pub s: V,
+    pub t: V,
+    pub n: N,
+}
+
 impl Frame {
     #[inline(always)] pub fn ct<A: Conv<F3>>(v: A) -> F { v.conv()[Z] }
     #[inline(always)] pub fn st<A: Conv<F3>>(v: A) -> F { F::sqrt(Self::s2t(v)) }
@@ -13,11 +23,65 @@ impl Frame {
     #[inline(always)] pub fn s2t<A: Conv<F3>>(v: A) -> F { F::max(1. - Self::c2t(v), 0.) }
     #[inline(always)] pub fn t2t<A: Copy + Conv<F3>>(v: A) -> F { Self::s2t(v) / Self::c2t(v) }
 
+    #[inline(always)] pub fn cp<A: Copy + Conv<F3>>(v: A) -> F {
+        let st = Self::st(v);
+        if st == 0. { 1. } else { (v.conv()[X] / st).clamp(-1., 1.) }
+    }
+
+    #[inline(always)] pub fn sp<A: Copy + Conv<F3>>(v: A) -> F {
+        let st = Self::st(v);
+        if st == 0. { 0. } else { (v.conv()[Y] / st).clamp(-1., 1.) }
+    }
+
+    // Long-form names for the shorthand above
+    #[inline(always)] pub fn cos_phi<A: Copy + Conv<F3>>(v: A) -> F { Self::cp(v) }
+    #[inline(always)] pub fn sin_phi<A: Copy + Conv<F3>>(v: A) -> F { Self::sp(v) }
+    #[inline(always)] pub fn tan2_theta<A: Copy + Conv<F3>>(v: A) -> F { Self::t2t(v) }
+
     #[inline(always)] pub fn reflect<A: Conv<F3>>(v: A) -> F3 {
         let v = v.conv();
         A3(-v[X], -v[Y], v[Z])
     }
 
+    // Refraction of `wi` through the local surface with relative index of
+    // refraction `eta` (inside over outside); `None` on total internal reflection
+    #[inline] pub fn refract<A: Copy + Conv<F3>>(wi: A, eta: F) -> Option<F3> {
+        let v = wi.conv();
+        let (eta, sign) = if v[Z] < 0. { (eta.inv(), -1.) } else { (eta, 1.) };
+        let s2t = Self::s2t(wi) / eta.sq();
+        if s2t >= 1. { return None }
+        Some(A3(-v[X] / eta, -v[Y] / eta, -sign * F::sqrt(1. - s2t)))
+    }
+
+    #[inline] pub fn fresnel_dielectric<A: Conv<F3>>(wi: A, eta: F) -> F {
+        let ct = Self::ct(wi).clamp(-1., 1.);
+        let (eta, ct_i) = if ct < 0. { (eta.inv(), -ct) } else { (eta, ct) };
+        let s2t = (1. - ct_i.sq()) / eta.sq();
+        if s2t >= 1. { return 1. }
+        let ct_t = F::sqrt(1. - s2t);
+        let r_parl = eta.mul_add(ct_i, -ct_t) / eta.mul_add(ct_i, ct_t);
+        let r_perp = eta.mul_add(-ct_t, ct_i) / eta.mul_add(ct_t, ct_i);
+        0.5 * r_parl.mul_add(r_parl, r_perp.sq())
+    }
+
+    // Complex index of refraction `eta + ik`
+    #[inline] pub fn fresnel_conductor<A: Conv<F3>>(wi: A, eta: F, k: F) -> F {
+        let ct = Self::ct(wi).clamp(-1., 1.).abs();
+        let (c2t, s2t) = (ct.sq(), 1. - ct.sq());
+        let t0 = eta.sq() - k.sq() - s2t;
+        let a2b2 = F::sqrt(t0.mul_add(t0, 4. * eta.sq() * k.sq()));
+        let t1 = a2b2 + c2t;
+        let t2 = 2. * ct * F::sqrt(0.5 * (a2b2 + t0));
+        let rs = (t1 - t2) / (t1 + t2);
+        let t3 = c2t.mul_add(a2b2, s2t.sq());
+        let t4 = t2 * s2t;
+        let rp = rs * (t3 - t4) / (t3 + t4);
+        0.5 * (rp + rs)
+    }
+
+    #[inline(always)] pub fn fresnel_schlick<A: Conv<F3>>(wi: A, r0: F) -> F
+    { (1. - r0).mul_add((1. - Self::ct(wi).abs()).powi(5), r0) }
+
     #[inline(always)] pub fn same_hemisphere<A, B>(v1: A, v2: B) -> bool
         where A: Conv<F3>, B: Conv<F3>
     { F3::dot(v1.conv(), v2.conv()) >= 0. }
@@ -35,3 +99,102 @@ impl Frame {
         A3(st * F::cos(v[1]), st * F::sin(v[1]), F::cos(v[0]))
     }
 }
+
+impl ShadingFrame {
+    #[inline] pub fn new(n: N) -> Self {
+        let (s, t) = conv!(n => V).coordinate_system();
+        Self { s, t, n }
+    }
+
+    // Gram-Schmidt orthogonalization of `s` against `n`; falls back to an
+    // arbitrary tangent if `s` is (nearly) parallel to `n`
+    #[inline] pub fn with_tangent(n: N, s: V) -> Self {
+        let n_v: V = n.conv();
+        let st = s - n_v * s.dot(n);
+        if st.norm2() <= F::EPS.sq() * s.norm2() { return Self::new(n) }
+        let s = st.unit();
+        Self { s, t: n_v.cross(s), n }
+    }
+
+    #[inline(always)] pub fn to_local<A>(&self, a: A) -> A where A: Conv<F3>, F3: Conv<A> {
+        let v = V(a.conv());
+        A3(v.dot(self.s), v.dot(self.t), v.dot(self.n)).conv()
+    }
+
+    #[inline(always)] pub fn to_world<A>(&self, a: A) -> A where A: Conv<F3>, F3: Conv<A> {
+        let A3(x, y, z) = a.conv();
+        (self.s * x + self.t * y + conv!(self.n => V) * z).0.conv()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    const A: F3 = A3(0.3, -2., 1.);
+
+    fn check(f: ShadingFrame) {
+        let n: V = f.n.conv();
+        assert!(f.s.dot(f.t).abs() < F::EPS && f.s.dot(n).abs() < F::EPS
+                && f.t.dot(n).abs() < F::EPS);
+        assert!(close((f.s * f.t).0, n.0));
+        let v = V(A);
+        assert!(close(f.to_world(f.to_local(v)).0, A));
+        assert!((f.to_local(v).norm() - v.norm()).abs() < F::EPS);
+        assert!((Frame::ct(f.to_local(v)) - v.dot(f.n)).abs() < F::EPS);
+        assert!(close(conv!(f.to_local(f.n) => F3), A3(0., 0., 1.)));
+    }
+
+    #[test] fn from_normal() {
+        for &n in &[A, -A, A3(0., 0., 1.), A3(0., 0., -1.), A3(1., 0., 0.)] {
+            check(ShadingFrame::new(n.conv()));
+        }
+    }
+
+    #[test] fn from_tangent() {
+        let n: N = A3(0., 1., 1.).conv();
+        let f = ShadingFrame::with_tangent(n, V(A3(2., 1., 1.)));
+        check(f);
+        assert!(close(f.s.0, A3(1., 0., 0.)));
+        check(ShadingFrame::with_tangent(n, n.conv()));
+    }
+
+    #[test] fn refract() {
+        let wi = Frame::spher2cart(A2(0.6, 1.));
+        let wt = Frame::refract(wi, 1.5).unwrap();
+        assert!((Frame::st(wt) * 1.5 - Frame::st(wi)).abs() < F::EPS);
+        assert!(wt[Z] < 0. && (Frame::cp(wt) + Frame::cp(wi)).abs() < F::EPS);
+        assert!(close(Frame::refract(wt, 1.5).unwrap(), wi));
+        assert!(close(Frame::refract(A3(0., 0., 1.), 1.5).unwrap(), A3(0., 0., -1.)));
+        assert_eq!(Frame::refract(Frame::spher2cart(A2(2.2, 0.)), 1.5), None);
+    }
+
+    #[test] fn fresnel() {
+        let n = A3(0., 0., 1.);
+        assert!((Frame::fresnel_dielectric(n, 1.5) - 0.04).abs() < F::EPS);
+        assert!((Frame::fresnel_dielectric(-n, 1.5) - 0.04).abs() < F::EPS);
+        let wi = Frame::spher2cart(A2(2.2, 0.));
+        assert!((Frame::fresnel_dielectric(wi, 1.5) - 1.).abs() < F::EPS);
+        for &theta in &[0., 0.3, 0.9, 1.4] {
+            let wi = Frame::spher2cart(A2(theta, 0.));
+            assert!((Frame::fresnel_conductor(wi, 1.5, 0.)
+                     - Frame::fresnel_dielectric(wi, 1.5)).abs() < 1e-3);
+            let f = Frame::fresnel_conductor(wi, 0.2, 3.);
+            assert!((0. ..=1.).contains(&f));
+        }
+        assert!((Frame::fresnel_schlick(n, 0.04) - 0.04).abs() < F::EPS);
+        assert!((Frame::fresnel_schlick(A3(1., 0., 0.), 0.04) - 1.).abs() < F::EPS);
+    }
+
+    #[test] fn phi() {
+        let v = Frame::spher2cart(A2(0.7, 2.));
+        assert!((Frame::cp(v) - F::cos(2.)).abs() < F::EPS);
+        assert!((Frame::sp(v) - F::sin(2.)).abs() < F::EPS);
+        assert!((Frame::t2t(v) - F::tan(0.7).sq()).abs() < F::EPS);
+        assert!((Frame::cp(A3(0., 0., 1.)) - 1.).abs() < F::EPS);
+        assert!(close(A3(Frame::cos_phi(v), Frame::sin_phi(v), Frame::tan2_theta(v)),
+                      A3(Frame::cp(v), Frame::sp(v), Frame::t2t(v))));
+    }
+}
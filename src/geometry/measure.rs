@@ -0,0 +1,57 @@
+use super::*;
+
+// Conversions of densities between area measure at a point `q` with normal
+// `n` and solid-angle measure as seen from `p`; grazing and coincident
+// configurations have zero density
+
+#[inline] pub fn pdf_area_to_solid_angle(pdf: F, p: P, q: P, n: N) -> F {
+    let w = q - p;
+    let ct = n.abs_dot(w);
+    if ct > 0. { pdf * w.norm2() * w.norm() / ct } else { 0. }
+}
+
+#[inline] pub fn pdf_solid_angle_to_area(pdf: F, p: P, q: P, n: N) -> F {
+    let w = q - p;
+    let d2 = w.norm2();
+    if d2 > 0. { pdf * n.abs_dot(w) / (d2 * d2.sqrt()) } else { 0. }
+}
+
+// Geometric coupling term `|cos(theta_p)| |cos(theta_q)| / |q - p|^2` of
+// the points `p` and `q` with normals `np` and `nq`, without visibility
+#[inline] pub fn geometric_term(p: P, np: N, q: P, nq: N) -> F {
+    let w = q - p;
+    let d2 = w.norm2();
+    if d2 > 0. { np.abs_dot(w) * nq.abs_dot(w) / d2.sq() } else { 0. }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+    use crate::conv;
+
+    const P0: P = P(A3(0.5, -1., 2.));
+    const Q: P = P(A3(1.5, 1., 0.));
+
+    fn n() -> N { conv!(A3(0., 1., 0.) => N) }
+
+    #[test] fn conversions() {
+        // `Q` is 3 units away from `P0`, seen at cos(theta) = 2 / 3 to its normal
+        let (d2, ct) = (9., 2. / 3.);
+        assert!(close(pdf_area_to_solid_angle(0.25, P0, Q, n()), 0.25 * d2 / ct));
+        assert!(close(pdf_solid_angle_to_area(0.25, P0, Q, n()), 0.25 * ct / d2));
+        let pdf = pdf_area_to_solid_angle(0.3, P0, Q, n());
+        assert!(close(pdf_solid_angle_to_area(pdf, P0, Q, n()), 0.3));
+        let tangent = conv!(A3(2., -1., 0.) => N);
+        assert!(close(pdf_area_to_solid_angle(0.25, P0, Q, tangent), 0.));
+        assert!(close(pdf_solid_angle_to_area(0.25, P0, P0, n()), 0.));
+    }
+
+    #[test] fn geometric() {
+        let np = conv!(A3(1., 2., -2.) => N);
+        assert!(close(geometric_term(P0, np, Q, n()), 2. / 3. / 9.));
+        assert!(close(geometric_term(P0, np, Q, n()), geometric_term(Q, n(), P0, np)));
+        assert!(close(geometric_term(P0, np, P0, n()), 0.));
+    }
+}
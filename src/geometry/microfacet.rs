@@ -0,0 +1,160 @@
+use super::*;
+
+// Microfacet normal distribution with Smith masking (Walter et al.,
+// "Microfacet Models for Refraction through Rough Surfaces"); directions are
+// in the local frame
+pub trait Microfacet {
+    fn alpha(&self) -> F2;
+
+    fn d<A: Copy + Conv<F3>>(&self, wh: A) -> F;
+    fn lambda<A: Copy + Conv<F3>>(&self, w: A) -> F;
+
+    // Samples a microfacet normal proportional to `d(wh) * cos(theta_h)`
+    fn sample(&self, s: F2) -> F3;
+
+    #[inline(always)] fn g1<A: Copy + Conv<F3>>(&self, w: A) -> F
+    { (1. + self.lambda(w)).inv() }
+
+    #[inline(always)] fn g<A, B>(&self, wo: A, wi: B) -> F
+        where A: Copy + Conv<F3>, B: Copy + Conv<F3>
+    { (1. + self.lambda(wo) + self.lambda(wi)).inv() }
+
+    #[inline(always)] fn pdf<A: Copy + Conv<F3>>(&self, wh: A) -> F
+    { self.d(wh) * Frame::ct(wh).abs() }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct Beckmann {
+    alpha: F2,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct TrowbridgeReitz {
+    alpha: F2,
+}
+
+// Perceptually linear roughness in [0, 1] to distribution width
+#[inline(always)] pub fn roughness_to_alpha(roughness: F) -> F { roughness.max(0.).sqrt() }
+
+impl Beckmann {
+    #[inline(always)] pub const fn new(alpha: F) -> Self { Self::anisotropic(A2(alpha, alpha)) }
+    #[inline(always)] pub const fn anisotropic(alpha: F2) -> Self { Self { alpha } }
+}
+
+impl TrowbridgeReitz {
+    #[inline(always)] pub const fn new(alpha: F) -> Self { Self::anisotropic(A2(alpha, alpha)) }
+    #[inline(always)] pub const fn anisotropic(alpha: F2) -> Self { Self { alpha } }
+}
+
+impl Microfacet for Beckmann {
+    #[inline(always)] fn alpha(&self) -> F2 { self.alpha }
+
+    #[inline] fn d<A: Copy + Conv<F3>>(&self, wh: A) -> F {
+        let e = slope2(self.alpha, wh);
+        if !e.is_finite() || Frame::ct(wh) <= 0. { return 0. }
+        F::exp(-e) * F::INV_PI / (self.alpha.product() * Frame::c2t(wh).sq())
+    }
+
+    // Rational approximation of the exact erfc-based form
+    #[inline] fn lambda<A: Copy + Conv<F3>>(&self, w: A) -> F {
+        let a = (projected_alpha(self.alpha, w) * Frame::tt(w).abs()).inv();
+        if a >= 1.6 { return 0. }
+        a.mul_add(a.mul_add(0.396, -1.259), 1.) / a.mul_add(a.mul_add(2.181, 3.535), 0.)
+    }
+
+    #[inline] fn sample(&self, s: F2) -> F3 {
+        let (phi, a2_inv) = sample_phi(self.alpha, s[Y]);
+        sample_normal(-F::ln(1. - s[X]) / a2_inv, phi)
+    }
+}
+
+impl Microfacet for TrowbridgeReitz {
+    #[inline(always)] fn alpha(&self) -> F2 { self.alpha }
+
+    #[inline] fn d<A: Copy + Conv<F3>>(&self, wh: A) -> F {
+        let e = slope2(self.alpha, wh);
+        if !e.is_finite() || Frame::ct(wh) <= 0. { return 0. }
+        F::INV_PI / (self.alpha.product() * Frame::c2t(wh).sq() * (1. + e).sq())
+    }
+
+    #[inline] fn lambda<A: Copy + Conv<F3>>(&self, w: A) -> F {
+        let a2t = projected_alpha(self.alpha, w).sq() * Frame::t2t(w);
+        if !a2t.is_finite() { return 0. }
+        0.5 * (F::sqrt(1. + a2t) - 1.)
+    }
+
+    #[inline] fn sample(&self, s: F2) -> F3 {
+        let (phi, a2_inv) = sample_phi(self.alpha, s[Y]);
+        sample_normal(s[X] / ((1. - s[X]) * a2_inv), phi)
+    }
+}
+
+// tan^2(theta) scaled by the squared width along the azimuth of `w`
+#[inline(always)] fn slope2<A: Copy + Conv<F3>>(alpha: F2, w: A) -> F {
+    let a2 = alpha.map(F::sq);
+    Frame::t2t(w) * (Frame::cp(w).sq() / a2[X] + Frame::sp(w).sq() / a2[Y])
+}
+
+#[inline(always)] fn projected_alpha<A: Copy + Conv<F3>>(alpha: F2, w: A) -> F
+{ F::sqrt(F2::dot(A2(Frame::cp(w), Frame::sp(w)).map(F::sq), alpha.map(F::sq))) }
+
+// Azimuth of an anisotropic distribution and the inverse squared width along it
+#[allow(clippy::float_cmp)]
+#[inline] fn sample_phi(alpha: F2, s: F) -> (F, F) {
+    let phi = if alpha[X] == alpha[Y] { F::TWO_PI * s } else {
+        let phi = F::atan(alpha[Y] / alpha[X] * F::tan(F::TWO_PI.mul_add(s, F::HALF_PI)));
+        if s > 0.5 { phi + F::PI } else { phi }
+    };
+    let a2 = alpha.map(F::sq);
+    (phi, phi.cos().sq() / a2[X] + phi.sin().sq() / a2[Y])
+}
+
+#[inline(always)] fn sample_normal(t2t: F, phi: F) -> F3
+{ Frame::spher2cart(A2(F::atan(t2t.sqrt()), phi)) }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    fn check_microfacet(m: &impl Microfacet) {
+        check(|s| m.sample(s), |wh| m.pdf(wh));
+        let wo = Frame::spher2cart(A2(1., 0.5));
+        let proj = integrate(F::HALF_PI, |wh| m.d(wh) * F3::dot(wo, wh).max(0.)) * m.g1(wo);
+        assert!((proj - Frame::ct(wo)).abs() < 2e-2);
+        assert!((m.g(wo, wo) - m.lambda(wo).mul_add(2., 1.).inv()).abs() < F::EPS);
+        assert!((m.g1(A3(0., 0., 1.)) - 1.).abs() < F::EPS);
+    }
+
+    #[test] fn beckmann() {
+        check_microfacet(&Beckmann::new(0.3));
+        check_microfacet(&Beckmann::anisotropic(A2(0.2, 0.5)));
+    }
+
+    #[test] fn trowbridge_reitz() {
+        check_microfacet(&TrowbridgeReitz::new(0.3));
+        check_microfacet(&TrowbridgeReitz::anisotropic(A2(0.2, 0.5)));
+    }
+
+    #[test] fn below_horizon() {
+        let wh = Frame::spher2cart(A2(2.5, 0.7));
+        let (b, t) = (Beckmann::new(0.3), TrowbridgeReitz::new(0.3));
+        assert!(b.d(wh) == 0. && b.pdf(wh) == 0.);
+        assert!(t.d(wh) == 0. && t.pdf(wh) == 0.);
+    }
+
+    #[test] fn matches_beckmann_warp() {
+        let m = Beckmann::new(roughness_to_alpha(0.2));
+        for i in 0..50 {
+            let wh = Frame::spher2cart(A2(F::of(i) * 0.03, F::of(i) * 0.4));
+            let p = BeckmannHemisphere::pdf(wh, m.alpha()[X]);
+            assert!((m.pdf(wh) - p).abs() <= 1e-3 * p.max(1.));
+            let s = A2(F::of(i) * 0.019, 0.3);
+            assert!((Frame::ct(m.sample(s)) - Frame::ct(BeckmannHemisphere::warp(s, m.alpha()[X])))
+                    .abs() < F::EPS);
+        }
+    }
+}
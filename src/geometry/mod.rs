@@ -1,9 +1,14 @@
 mod bbox;
 mod bound;
+mod bvh;
+mod chi2;
 mod frame;
+mod measure;
+mod microfacet;
 mod normal3;
 mod point3;
 mod ray3;
+mod shape;
 mod transform;
 mod vector3;
 mod warp;
@@ -12,10 +17,15 @@ use crate::core::*;
 
 pub use bbox::*;
 pub use bound::*;
+pub use bvh::*;
+pub use chi2::*;
 pub use frame::*;
+pub use measure::*;
+pub use microfacet::*;
 pub use normal3::*;
 pub use point3::*;
 pub use ray3::*;
+pub use shape::*;
 pub use transform::*;
 pub use vector3::*;
 pub use warp::*;
@@ -10,6 +10,18 @@ pub struct N(pub(crate) V);
 
 impl Zero for N { const ZERO: Self = N(V::ZERO); }
 
+impl N {
+    #[must_use]
+    #[inline(always)] pub fn face_forward(self, v: V) -> N
+    { if self.dot(v) < 0. { -self } else { self } }
+
+    #[must_use]
+    #[inline(always)] pub fn abs(self) -> N { N(self.0.abs()) }
+}
+
+impl Dot for N { #[inline(always)] fn dot(self, n: N) -> F { self.0.dot(n.0) } }
+impl Dot<V> for N { #[inline(always)] fn dot(self, v: V) -> F { self.0.dot(v) } }
+
 op!(Neg::neg, *N);
 op!(Add::add, *N -> *N -> N);
 op!(Mul::mul, *N ->  F -> N);
@@ -10,6 +10,26 @@ pub struct P(pub(crate) F3);
 
 impl Zero for P { const ZERO: P = P(F3::ZERO); }
 
+impl P {
+    #[inline(always)] pub fn distance2(self, p: P) -> F { (self - p).norm2() }
+    #[inline(always)] pub fn distance(self, p: P) -> F { (self - p).norm() }
+
+    #[must_use]
+    #[inline(always)] pub fn lerp(self, p: P, t: F) -> P
+    { P(LinearScale::interp(A2(self.0, p.0), t)) }
+
+    #[must_use]
+    #[inline(always)] pub fn min(self, p: P) -> P { P(self.0.zip(p.0, F::min)) }
+    #[must_use]
+    #[inline(always)] pub fn max(self, p: P) -> P { P(self.0.zip(p.0, F::max)) }
+    #[must_use]
+    #[inline(always)] pub fn abs(self) -> P { P(self.0.map(F::abs)) }
+
+    #[must_use]
+    #[inline(always)] pub fn permute(self, x: Dim, y: Dim, z: Dim) -> P
+    { P(A3(self[x], self[y], self[z])) }
+}
+
 op!(Add::add, *P -> *P -> P);
 op!(Add::add, *P ->  F -> P);
 op!(Sub::sub, *P ->  F -> P);
@@ -26,11 +46,19 @@ impl Index<Dim> for P {
 }
 
 
-#[cfg(feature="serde-derive")]
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test] fn distance() {
+        let (a, b) = (P(A3(1., 2., 3.)), P(A3(4., -2., 3.)));
+        assert!((a.distance(b) - 5.).abs() < F::EPS);
+        assert!((a.distance2(b) - 25.).abs() < F::EPS);
+        assert_eq!(a.lerp(b, 0.5), P(A3(2.5, 0., 3.)));
+        assert_eq!(a.min(b).max(P::ZERO), P(A3(1., 0., 3.)));
+    }
+
+    #[cfg(feature="serde-derive")]
     #[test] fn deser_p() {
         assert_eq!(serde_json::from_str::<P>("[-1, 1, 0.5]").unwrap(),
                    P(A3(-1., 1., 0.5)));
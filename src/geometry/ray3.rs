@@ -21,11 +21,31 @@ impl R {
     #[inline(always)] pub fn range(&self) -> B { B::b(F::EPS, self.t - F::EPS) }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RInv {
+    pub o:   P,
+    pub d:   F3,
+    pub neg: A3<bool>,
+    pub t:   F,
+}
+
+impl RInv {
+    #[inline(always)] pub fn range(&self) -> B { B::b(F::EPS, self.t - F::EPS) }
+}
+
+impl Conv<RInv> for R {
+    #[inline(always)] fn conv(self) -> RInv {
+        let d = self.d.0.map(F::inv);
+        RInv { o: self.o, d, neg: d.map(|f| f < 0.), t: self.t }
+    }
+}
+
 impl Mul<R> for T {
     type Output = R;
     #[inline(always)] fn mul(self, R { o, d, t }: R) -> R { R::r(self * o, self * d, t) }
 }
 
+// The direction is left unnormalized so that `t` is the same in both spaces
 impl Div<R> for T {
     type Output = R;
     #[inline(always)] fn div(self, R { o, d, t }: R) -> R { R::r(self / o, self / d, t) }
@@ -0,0 +1,67 @@
+use super::*;
+use crate::conv;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct Cylinder {
+    r:       F,
+    z:       B,
+    phi_max: F,
+}
+
+impl Cylinder {
+    // Cylinder of radius `r` about the z-axis, clipped to `z` and swept from 0
+    // to `phi_max` degrees
+    #[inline(always)] pub const fn new(r: F, z: B, phi_max: F) -> Self
+    { Self { r, z, phi_max: phi_max.clamp(0., 360.).to_radians() } }
+}
+
+impl Bounded for Cylinder {
+    #[inline(always)] fn bbox(&self) -> BBox
+    { BBox(A3(B::b(-self.r, self.r), B::b(-self.r, self.r), self.z)) }
+}
+
+impl Shape for Cylinder {
+    #[inline(always)] fn area(&self) -> F { self.phi_max * self.r * self.z.extent() }
+
+    #[inline(always)] fn normal(&self, p: P) -> N { conv!(A3(p[X], p[Y], 0.) => N) }
+
+    #[inline] fn intersect(&self, r: R) -> Option<Hit> {
+        let (org, dir) = (A2(r.o[X], r.o[Y]), A2(r.d[X], r.d[Y]));
+        let a = F2::dot(dir, dir);
+        if a == 0. { return None }
+        let ts = quad(a, 2. * F2::dot(org, dir), F2::dot(org, org) - self.r.sq())?;
+        nearest(r, ts, |t| {
+            let p = r.at(t);
+            let phi = super::phi(p);
+            if !self.z.bounds(p[Z]) || phi > self.phi_max { return None }
+            let uv = A2(phi / self.phi_max, (p[Z] - self.z.lower()) / self.z.extent());
+            Some(Hit { t, p, n: self.normal(p), uv })
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test] fn hit() {
+        let c = Cylinder::new(1., B::b(-1., 1.), 360.);
+        let r = R::unbounded(P(A3(-3., 0., 0.5)), V(A3(1., 0., 0.)));
+        let h = c.intersect(r).unwrap();
+        assert!((h.t - 2.).abs() < F::EPS);
+        assert_eq!(h.n, conv!(A3(-1., 0., 0.) => N));
+        assert!((h.uv - A2(0.5, 0.75)).map(F::abs).max() < F::EPS);
+    }
+
+    #[test] fn miss() {
+        let c = Cylinder::new(1., B::b(-1., 1.), 180.);
+        let along = R::unbounded(P(A3(0., 0., -3.)), V(A3(0., 0., 1.)));
+        assert_eq!(c.intersect(along), None);
+        let above = R::unbounded(P(A3(-3., 0., 2.)), V(A3(1., 0., 0.)));
+        assert_eq!(c.intersect(above), None);
+        let swept = R::unbounded(P(A3(0., -3., 0.)), V(A3(0., 1., 0.)));
+        assert!((c.intersect(swept).unwrap().p[Y] - 1.).abs() < F::EPS);
+    }
+}
@@ -0,0 +1,70 @@
+use super::*;
+use crate::conv;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct Disk {
+    pub c: P,
+    pub n: N,
+    pub r: F,
+}
+
+impl Disk {
+    #[inline(always)] pub const fn new(c: P, n: N, r: F) -> Self { Self { c, n, r } }
+}
+
+impl Bounded for Disk {
+    #[inline(always)] fn bbox(&self) -> BBox {
+        let n = conv!(self.n => F3);
+        BBox(self.c.0.zip(n, |c, n| {
+            let e = self.r * F::sqrt(F::max(1. - n.sq(), 0.));
+            B::b(c - e, c + e)
+        }))
+    }
+}
+
+impl Shape for Disk {
+    #[inline(always)] fn area(&self) -> F { F::PI * self.r.sq() }
+
+    #[inline(always)] fn normal(&self, _: P) -> N { self.n }
+
+    #[inline] fn intersect(&self, r: R) -> Option<Hit> {
+        let t = self.n.dot(self.c - r.o) / self.n.dot(r.d);
+        if !r.range().bounds(t) { return None }
+        let p = r.at(t);
+        let local = T::from_frame(self.n) / (p - self.c);
+        let rho2 = local[X].sq() + local[Y].sq();
+        if rho2 > self.r.sq() { return None }
+        let uv = A2(super::phi(local) * F::INV_2PI, rho2.sqrt() / self.r);
+        Some(Hit { t, p, n: self.n, uv })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disk() -> Disk
+    { Disk::new(P(A3(1., 1., 1.)), conv!(A3(0., 1., 1.) => N), 2.) }
+
+    #[test] fn hit() {
+        let r = R::unbounded(P(A3(1., 4., 4.)), V(A3(0., -1., -1.)));
+        let h = disk().intersect(r).unwrap();
+        assert!((h.p.0 - A3(1., 1., 1.)).map(F::abs).max() < F::EPS);
+        assert!(h.uv[Y] < F::EPS);
+    }
+
+    #[test] fn miss() {
+        let r = R::unbounded(P(A3(4., 4., 4.)), V(A3(0., -1., -1.)));
+        assert_eq!(disk().intersect(r), None);
+        let r = R::unbounded(P(A3(1., 4., 4.)), V(A3(1., 0., 0.)));
+        assert_eq!(disk().intersect(r), None);
+    }
+
+    #[test] fn bbox() {
+        let b = disk().bbox();
+        assert!((b[X].extent() - 4.).abs() < F::EPS);
+        assert!((b[Y].extent() - F::sqrt(8.)).abs() < F::EPS);
+    }
+}
@@ -0,0 +1,56 @@
+mod cylinder;
+mod disk;
+mod plane;
+mod sphere;
+mod triangle;
+
+use std::ops::Mul;
+
+use super::*;
+
+pub use cylinder::*;
+pub use disk::*;
+pub use plane::*;
+pub use sphere::*;
+pub use triangle::*;
+
+// `uv` holds the surface parameterization of the hit point; for triangles
+// these are the barycentric weights of the second and third vertex
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit {
+    pub t:  F,
+    pub p:  P,
+    pub n:  N,
+    pub uv: F2,
+}
+
+// Sphere, Disk, Plane and Triangle are placed directly in world space and
+// their fields are public. PartialSphere and Cylinder are canonical quadrics
+// about the z-axis; their fields are private because `new` stores the sweep
+// in radians. Any shape can be instanced by intersecting `t / r` and mapping
+// the hit back with `t * hit`. `t / r` keeps the transformed direction
+// unnormalized, so the object-space `t` of the hit is also its world-space `t`
+pub trait Shape: Bounded {
+    fn area(&self) -> F;
+    fn normal(&self, p: P) -> N;
+    fn intersect(&self, r: R) -> Option<Hit>;
+
+    #[inline(always)] fn intersects(&self, r: R) -> bool { self.intersect(r).is_some() }
+}
+
+impl Mul<Hit> for T {
+    type Output = Hit;
+    #[inline(always)] fn mul(self, Hit { t, p, n, uv }: Hit) -> Hit
+    { Hit { t, p: self * p, n: self * n, uv } }
+}
+
+#[inline(always)] fn nearest(r: R, ts: F2, hit: impl Fn(F) -> Option<Hit>) -> Option<Hit> {
+    let ts: [F; 2] = ts.conv();
+    ts.iter().copied().filter(|&t| r.range().bounds(t)).find_map(hit)
+}
+
+#[inline(always)] fn phi<A: Conv<F3>>(v: A) -> F {
+    let v = v.conv();
+    let phi = F::atan2(v[Y], v[X]);
+    if phi < 0. { phi + F::TWO_PI } else { phi }
+}
@@ -0,0 +1,68 @@
+use super::*;
+use crate::conv;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct Plane {
+    pub p: P,
+    pub n: N,
+}
+
+impl Plane {
+    #[inline(always)] pub const fn new(p: P, n: N) -> Self { Self { p, n } }
+}
+
+impl Bounded for Plane {
+    #[inline(always)] fn bbox(&self) -> BBox {
+        let n = conv!(self.n => F3);
+        BBox(self.p.0.zip(n, |p, n| if n.abs() >= 1. { B::point(p) }
+                                    else { B::b(F::NEG_INF, F::POS_INF) }))
+    }
+}
+
+impl Shape for Plane {
+    #[inline(always)] fn area(&self) -> F { F::POS_INF }
+
+    #[inline(always)] fn normal(&self, _: P) -> N { self.n }
+
+    #[inline] fn intersect(&self, r: R) -> Option<Hit> {
+        let t = self.n.dot(self.p - r.o) / self.n.dot(r.d);
+        if !r.range().bounds(t) { return None }
+        let p = r.at(t);
+        let local = T::from_frame(self.n) / (p - self.p);
+        Some(Hit { t, p, n: self.n, uv: A2(local[X], local[Y]) })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test] fn hit() {
+        let pl = Plane::new(P(A3(0., 2., 0.)), conv!(A3(0., -1., 0.) => N));
+        let r = R::unbounded(P(A3(3., 0., -7.)), V(A3(0., 1., 1.)));
+        let h = pl.intersect(r).unwrap();
+        assert!((h.t - F::sqrt(8.)).abs() < F::EPS);
+        assert_eq!(pl.bbox()[Y], B::point(2.));
+        assert_eq!(pl.bbox()[X], B::b(F::NEG_INF, F::POS_INF));
+    }
+
+    #[test] fn miss() {
+        let pl = Plane::new(P::ZERO, conv!(A3(0., 0., 1.) => N));
+        let away = R::unbounded(P(A3(0., 0., 1.)), V(A3(0., 0., 1.)));
+        assert_eq!(pl.intersect(away), None);
+        let parallel = R::unbounded(P(A3(0., 0., 1.)), V(A3(1., 0., 0.)));
+        assert_eq!(pl.intersect(parallel), None);
+    }
+
+    #[test] fn instanced() {
+        let t = T::translate(A3(0., 0., 3.)) * T::rotate(A3(1., 0., 0.), 90.);
+        let pl = Plane::new(P::ZERO, conv!(A3(0., 0., 1.) => N));
+        let r = R::unbounded(P(A3(1., 5., 3.)), V(A3(0., -1., 0.)));
+        let h = t * pl.intersect(t / r).unwrap();
+        assert!((h.t - 5.).abs() < F::EPS);
+        assert!((h.p.0 - A3(1., 0., 3.)).map(F::abs).max() < F::EPS);
+        assert!((conv!(h.n => F3) - A3(0., -1., 0.)).map(F::abs).max() < F::EPS);
+    }
+}
@@ -0,0 +1,213 @@
+use super::*;
+use crate::conv;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct Sphere {
+    pub c: P,
+    pub r: F,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct PartialSphere {
+    r:       F,
+    z:       B,
+    phi_max: F,
+}
+
+impl Sphere {
+    #[inline(always)] pub const fn new(c: P, r: F) -> Self { Self { c, r } }
+
+    // Point on the sphere, its normal and the solid-angle density of the
+    // direction to it from `p`: the cone subtended by the sphere is sampled
+    // uniformly from outside and the area uniformly from inside
+    #[inline] pub fn sample_solid_angle(&self, p: P, s: F2) -> (P, N, F) {
+        let Some(ct_max) = self.cone(p) else {
+            let q = self.c + V(UniformSphere::warp(s)) * self.r;
+            let n = self.normal(q);
+            return (q, n, pdf_area_to_solid_angle(self.area().inv(), p, q, n))
+        };
+        let dist = p.distance(self.c);
+        let axis: N = (self.c - p).conv();
+        let dir = V(ShadingFrame::new(axis).to_world(UniformCone::warp(s, ct_max)));
+        let ct = dir.dot(axis);
+        let chord2 = (dist * ct).mul_add(dist * ct, dist.mul_add(-dist, self.r.sq()));
+        let q = p + dir * dist.mul_add(ct, -chord2.max(0.).sqrt());
+        (q, self.normal(q), UniformCone::pdf(A3(0., 0., 1.), ct_max))
+    }
+
+    // Density of `sample_solid_angle` in direction `w` from `p`
+    #[inline] pub fn pdf_solid_angle(&self, p: P, w: V) -> F {
+        let Some(ct_max) = self.cone(p) else {
+            return self.intersect(R::unbounded(p, w)).map_or(0., |h| {
+                pdf_area_to_solid_angle(self.area().inv(), p, h.p, h.n)
+            })
+        };
+        UniformCone::pdf(A3(0., 0., w.unit().dot(conv!(self.c - p => N))), ct_max)
+    }
+
+    // Cosine of the half-angle subtended by the sphere from outside
+    #[inline] fn cone(&self, p: P) -> Option<F> {
+        let d2 = p.distance2(self.c);
+        if d2 <= self.r.sq() { return None }
+        Some(F::sqrt((1. - self.r.sq() / d2).max(0.)))
+    }
+}
+
+impl Conv<Sphere> for (P, F)
+{ #[inline(always)] fn conv(self) -> Sphere { Sphere::new(self.0, self.1) } }
+
+impl Bounded for Sphere {
+    #[inline(always)] fn bbox(&self) -> BBox
+    { BBox(self.c.0.map(|c| B::b(c - self.r, c + self.r))) }
+}
+
+impl Shape for Sphere {
+    #[inline(always)] fn area(&self) -> F { F::FOUR_PI * self.r.sq() }
+
+    #[inline(always)] fn normal(&self, p: P) -> N { conv!(p - self.c => N) }
+
+    #[inline] fn intersect(&self, r: R) -> Option<Hit> {
+        let o = r.o - self.c;
+        let ts = quad(r.d.norm2(), 2. * o.dot(r.d), o.norm2() - self.r.sq())?;
+        nearest(r, ts, |t| {
+            let v = r.at(t) - self.c;
+            let v = v * (self.r / v.norm());
+            let n = conv!(v => N);
+            let uv = Frame::cart2spher(n).flip() * A2(F::INV_2PI, F::INV_PI);
+            Some(Hit { t, p: self.c + v, n, uv })
+        })
+    }
+}
+
+impl PartialSphere {
+    // Sphere of radius `r` about the origin, clipped to `z` and swept from 0 to
+    // `phi_max` degrees about the z-axis
+    #[inline(always)] pub fn new(r: F, z: B, phi_max: F) -> Self {
+        let z = z & B::b(-r, r);
+        Self { r, z, phi_max: phi_max.clamp(0., 360.).to_radians() }
+    }
+
+    #[inline(always)] fn theta(&self) -> B
+    { B::ordered(self.z.0.map(|z| F::acos((z / self.r).clamp(-1., 1.)))) }
+}
+
+impl Bounded for PartialSphere {
+    #[inline(always)] fn bbox(&self) -> BBox
+    { BBox(A3(B::b(-self.r, self.r), B::b(-self.r, self.r), self.z)) }
+}
+
+impl Shape for PartialSphere {
+    #[inline(always)] fn area(&self) -> F { self.phi_max * self.r * self.z.extent() }
+
+    #[inline(always)] fn normal(&self, p: P) -> N { conv!(p.0 => N) }
+
+    #[inline] fn intersect(&self, r: R) -> Option<Hit> {
+        let o = r.o.0;
+        let ts = quad(r.d.norm2(), 2. * F3::dot(o, r.d.0), F3::dot(o, o) - self.r.sq())?;
+        nearest(r, ts, |t| {
+            let p = r.at(t).0;
+            let p = p * (self.r / F3::dot(p, p).sqrt());
+            let phi = phi(p);
+            if !self.z.bounds(p[Z]) || phi > self.phi_max { return None }
+            let theta = self.theta();
+            let ct = (p[Z] / self.r).clamp(-1., 1.);
+            let uv = A2(phi / self.phi_max,
+                        (F::acos(ct) - theta.lower()) / theta.extent());
+            Some(Hit { t, p: P(p), n: conv!(p => N), uv })
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    #[test] fn sphere_hit() {
+        let s = Sphere::new(P(A3(0., 0., 5.)), 2.);
+        let h = s.intersect(R::unbounded(P::ZERO, V(A3(0., 0., 1.)))).unwrap();
+        assert!(close(h.t, 3.));
+        assert_eq!(h.n, conv!(A3(0., 0., -1.) => N));
+        assert!(close(h.uv[Y], 1.));
+    }
+
+    #[test] fn sphere_from_inside() {
+        let s = Sphere::new(P::ZERO, 2.);
+        let h = s.intersect(R::unbounded(P::ZERO, V(A3(1., 0., 0.)))).unwrap();
+        assert!(close(h.t, 2.));
+        assert_eq!(s.normal(h.p), h.n);
+    }
+
+    #[test] fn sphere_miss() {
+        let s = Sphere::new(P(A3(0., 0., 5.)), 2.);
+        assert_eq!(s.intersect(R::unbounded(P::ZERO, V(A3(0., 1., 0.)))), None);
+        assert_eq!(s.intersect(R::r(P::ZERO, V(A3(0., 0., 1.)), 2.)), None);
+    }
+
+    #[test] fn sphere_bsphere() {
+        let b = BBox(A3(B::b(-1., 1.), B::b(0., 2.), B::b(-3., 3.)));
+        let s: Sphere = b.bsphere().conv();
+        assert_eq!(b | s.bbox(), s.bbox());
+    }
+
+    #[test] fn sphere_instanced() {
+        // an ellipsoid with semi-axes (2, 1, 1) about (0, 0, 5)
+        let t = T::translate(A3(0., 0., 5.)) * T::scale(A3(2., 1., 1.));
+        let s = Sphere::new(P::ZERO, 1.);
+        let h = t * s.intersect(t / R::unbounded(P(A3(-10., 0., 5.)), V(A3(1., 0., 0.)))).unwrap();
+        assert!(close(h.t, 8.));
+        assert!((h.p.0 - A3(-2., 0., 5.)).map(F::abs).max() < F::EPS);
+        let ray = R::unbounded(P(A3(0., 0., 5.)), V(A3(1., 1., 0.)));
+        let h = t * s.intersect(t / ray).unwrap();
+        assert!(close(h.t, F::sqrt(1.6)));
+        assert!((h.p - ray.at(h.t)).norm() < F::EPS);
+        let normal = V(A3(0.25, 1., 0.)).unit();
+        assert!((conv!(h.n => F3) - normal.0).map(F::abs).max() < F::EPS);
+    }
+
+    fn solid_angle(s: &Sphere, p: P) -> F {
+        const N: I = 64;
+        let mut sum = 0.;
+        for i in 0..N {
+            for j in 0..N {
+                let u = A2(F::of(i) + 0.5, F::of(j) + 0.5) / F::of(N);
+                let (q, normal, pdf) = s.sample_solid_angle(p, u);
+                assert!(close(q.distance(s.c), s.r));
+                assert!(close(normal.dot(conv!(q - s.c => N)), 1.));
+                assert!((pdf - s.pdf_solid_angle(p, q - p)).abs() < 1e-3 * pdf);
+                sum += pdf.inv();
+            }
+        }
+        sum / F::of(N * N)
+    }
+
+    #[test] fn sphere_solid_angle() {
+        let s = Sphere::new(P(A3(1., -2., 3.)), 0.5);
+        let p = P(A3(0., 1., 0.5));
+        let cone = F::TWO_PI * (1. - F::sqrt(1. - 0.25 / p.distance2(s.c)));
+        assert!((solid_angle(&s, p) - cone).abs() < 1e-3);
+        let (q, _, _) = s.sample_solid_angle(p, A2(0.3, 0.8));
+        assert!(s.intersect(R::unbounded(p, q - p)).is_some_and(|h| close(h.t, p.distance(q))));
+        assert!(s.pdf_solid_angle(p, -(s.c - p)).abs() < F::EPS);
+        assert!((solid_angle(&s, P(A3(1.2, -2., 3.1))) - F::FOUR_PI).abs() < 1e-1);
+    }
+
+    #[test] fn partial_sphere_clipped() {
+        let s = PartialSphere::new(1., B::b(-0.5, 0.5), 360.);
+        let down = R::unbounded(P(A3(0., 0., 2.)), V(A3(0., 0., -1.)));
+        assert_eq!(s.intersect(down), None);
+        let side = R::unbounded(P(A3(-2., 0., 0.)), V(A3(1., 0., 0.)));
+        assert!(close(s.intersect(side).unwrap().t, 1.));
+    }
+
+    #[test] fn partial_sphere_phi() {
+        let s = PartialSphere::new(1., B::b(-1., 1.), 90.);
+        let r = R::unbounded(P(A3(-2., 0.5, 0.)), V(A3(1., 0., 0.)));
+        let h = s.intersect(r).unwrap();
+        assert!(h.p[X] > 0.);
+        assert!(close(s.area(), F::PI));
+    }
+}
@@ -0,0 +1,172 @@
+use std::ops::BitOr;
+
+use super::*;
+use crate::conv;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct Triangle(pub A3<P>);
+
+impl Triangle {
+    #[inline(always)] fn cross(&self) -> V {
+        let A3(p0, p1, p2) = self.0;
+        (p1 - p0) * (p2 - p0)
+    }
+
+    #[inline(always)] fn hit(&self, t: F, bary: F3) -> Hit {
+        let p = P(self.0.zip(bary, |p, b| p.0 * b).sum());
+        Hit { t, p, n: self.normal(p), uv: A2(bary[Y], bary[Z]) }
+    }
+
+    // Möller and Trumbore, "Fast, Minimum Storage Ray/Triangle Intersection"
+    #[inline] pub fn intersect_mt(&self, r: R) -> Option<Hit> {
+        let A3(p0, p1, p2) = self.0;
+        let e1 = p1 - p0;
+        let e2 = p2 - p0;
+        let pv = r.d * e2;
+        let det = e1.dot(pv);
+        if det == 0. { return None }
+        let det_inv = det.inv();
+
+        let tv = r.o - p0;
+        let u = tv.dot(pv) * det_inv;
+        if !(0. ..=1.).contains(&u) { return None }
+        let qv = tv * e1;
+        let v = r.d.dot(qv) * det_inv;
+        if v < 0. || u + v > 1. { return None }
+
+        let t = e2.dot(qv) * det_inv;
+        if !r.range().bounds(t) { return None }
+        Some(self.hit(t, A3(1. - u - v, u, v)))
+    }
+
+    // Direction from `p` distributed uniformly over the solid angle of the
+    // triangle, and that solid angle
+    #[inline] pub fn sample_solid_angle(&self, p: P, s: F2) -> (V, F) {
+        let tri = self.0.map(|q| (q - p).0);
+        (V(SphericalTriangle::warp(s, tri)), SphericalTriangle::solid_angle(tri))
+    }
+}
+
+impl Bounded for Triangle {
+    #[inline(always)] fn bbox(&self) -> BBox { self.0.fold(BBox::ZERO, BitOr::bitor) }
+}
+
+impl Shape for Triangle {
+    #[inline(always)] fn area(&self) -> F { 0.5 * self.cross().norm() }
+    #[inline(always)] fn normal(&self, _: P) -> N { conv!(self.cross() => N) }
+
+    // Woop et al., "Watertight Ray/Triangle Intersection"
+    #[inline] fn intersect(&self, r: R) -> Option<Hit> {
+        let ad = r.d.0.map(F::abs);
+        let kz = if ad[0] > ad[1] { if ad[0] > ad[2] { 0 } else { 2 } }
+                 else if ad[1] > ad[2] { 1 } else { 2 };
+        let (kx, ky) = ((kz + 1) % 3, (kz + 2) % 3);
+
+        let dir = r.d.0.swizzle(kx, ky, kz);
+        let shear = A3(-dir[X] / dir[Z], -dir[Y] / dir[Z], dir[Z].inv());
+        let pt = self.0.map(|p| {
+            let p = (p - r.o).0.swizzle(kx, ky, kz);
+            A3(shear[X].mul_add(p[Z], p[X]), shear[Y].mul_add(p[Z], p[Y]), p[Z])
+        });
+
+        let A3(p0, p1, p2) = pt;
+        let e = A3(edge(p1, p2), edge(p2, p0), edge(p0, p1));
+        if (e[X] < 0. || e[Y] < 0. || e[Z] < 0.)
+           && (e[X] > 0. || e[Y] > 0. || e[Z] > 0.) { return None }
+        let det = e.sum();
+        if det == 0. { return None }
+
+        let t = F3::dot(e, pt.map(|p| p[Z] * shear[Z])) / det;
+        if !r.range().bounds(t) { return None }
+        Some(self.hit(t, e / det))
+    }
+
+}
+
+// Falls back to double precision when the edge function is exactly zero
+#[inline(always)] fn edge(a: F3, b: F3) -> F {
+    let e = difference_of_products(a[X], b[Y], a[Y], b[X]);
+    if e != 0. { return e }
+    let (a, b): (A3<f64>, A3<f64>) = (a.conv(), b.conv());
+    F::of(a[X].mul_add(b[Y], -a[Y] * b[X]))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tri(a: F3, b: F3, c: F3) -> Triangle { Triangle(A3(P(a), P(b), P(c))) }
+
+    fn unit() -> Triangle
+    { tri(A3(0., 0., 0.), A3(1., 0., 0.), A3(0., 1., 0.)) }
+
+    fn down(x: F, y: F) -> R
+    { R::unbounded(P(A3(x, y, 1.)), V(A3(0., 0., -1.))) }
+
+    #[test] fn hit() {
+        let h = unit().intersect(down(0.25, 0.5)).unwrap();
+        assert!((h.t - 1.).abs() < F::EPS);
+        assert!((h.uv - A2(0.25, 0.5)).map(F::abs).max() < F::EPS);
+        assert!((h.p.0 - A3(0.25, 0.5, 0.)).map(F::abs).max() < F::EPS);
+        assert_eq!(h.n, conv!(A3(0., 0., 1.) => N));
+    }
+
+    #[test] fn miss() {
+        assert_eq!(unit().intersect(down(0.75, 0.5)), None);
+        assert_eq!(unit().intersect(down(-0.1, 0.5)), None);
+    }
+
+    #[test] fn parallel() {
+        let r = R::unbounded(P(A3(-1., 0.25, 0.)), V(A3(1., 0., 0.)));
+        assert_eq!(unit().intersect(r), None);
+        assert_eq!(unit().intersect_mt(r), None);
+    }
+
+    #[test] fn clipped() {
+        assert_eq!(unit().intersect(down(0.25, 0.25).clipped(0.5)), None);
+        let r = R::unbounded(P(A3(0.25, 0.25, -1.)), V(A3(0., 0., -1.)));
+        assert_eq!(unit().intersect(r), None);
+    }
+
+    #[test] fn shared_edge_is_watertight() {
+        let a = tri(A3(0., 0., 0.), A3(1., 0., 0.3), A3(0.2, 1., -0.1));
+        let b = tri(A3(1., 0., 0.3), A3(1.3, 1.1, 0.), A3(0.2, 1., -0.1));
+        let A3(_, p1, p2) = a.0;
+        for i in 1..1000 {
+            let o = p1 + (p2 - p1) * (F::of(i) / 1000.) + V(A3(0.01, 0.02, 1.));
+            let ray = R::unbounded(o, V(A3(-0.01, -0.02, -1.)));
+            assert!(a.intersect(ray).is_some() || b.intersect(ray).is_some());
+        }
+    }
+
+    #[test] fn solid_angle() {
+        // the halves of a cube face seen from its center
+        let a = tri(A3(-1., -1., 1.), A3(1., -1., 1.), A3(1., 1., 1.));
+        let b = tri(A3(-1., -1., 1.), A3(1., 1., 1.), A3(-1., 1., 1.));
+        let (w, sa) = a.sample_solid_angle(P::ZERO, A2(0.3, 0.6));
+        let (_, sb) = b.sample_solid_angle(P::ZERO, A2(0.3, 0.6));
+        assert!((sa + sb - F::FOUR_PI / 6.).abs() < F::EPS);
+        assert!(a.intersects(R::unbounded(P::ZERO, w)));
+    }
+
+    #[test] fn matches_moller_trumbore() {
+        let t = tri(A3(-1., -0.5, 2.), A3(1.5, -1., 3.), A3(0., 2., 2.5));
+        for i in 0..40 { for j in 0..40 {
+            let d = A2(F::of(i), F::of(j)) / 20. - 1.;
+            let ray = R::unbounded(P(A3(0., 0., -1.)), V(A3(d[X], d[Y], 1.)));
+            match (t.intersect(ray), t.intersect_mt(ray)) {
+                (Some(h1), Some(h2)) => {
+                    assert!((h1.t - h2.t).abs() < F::EPS);
+                    assert!((h1.uv - h2.uv).map(F::abs).max() < F::EPS);
+                }
+                (None, None) => (),
+                (h1, h2) => {
+                    let uv = h1.or(h2).unwrap().uv;
+                    assert!(A3(1. - uv.sum(), uv[X], uv[Y]).min() < F::EPS);
+                }
+            }
+        }}
+    }
+}
@@ -29,10 +29,39 @@ impl Affine3 {
     #[inline(always)] pub fn from_frame<A: Conv<F3>>(v: A) -> Self
     { Self::new(RotScale3::from_frame(v), None) }
 
+    /// Cofactor inverse of the 3x3 part; the translation is then -R⁻¹t
+    ///
+    /// # Errors
+    /// Fails if the 3x3 part is singular
+    #[inline] pub fn inv(&self) -> Result<Self, SingularMatrix> {
+        let r = self.r.inv().ok_or(SingularMatrix)?;
+        Ok(Self::new(r, self.t.map(|t| -(r * t))))
+    }
+
+    // Translation, rotation and stretch such that self = T R S; `None` if the
+    // matrix is singular
+    #[inline] pub fn decompose(&self) -> Option<(F3, Quat, RotScale3)> {
+        let (r, s) = self.r.polar()?;
+        Some((self.t.unwrap_or(F3::ZERO), r.conv(), s))
+    }
+
     #[inline(always)] pub fn rot(&self) -> Self { Self::new(self.r, None) }
     #[inline(always)] pub fn t(&self) -> Self { Self::new(self.r.t(), None) }
 }
 
+impl Conv<Affine3> for Quat
+{ #[inline(always)] fn conv(self) -> Affine3 { Affine3::new(self.conv(), None) } }
+
+impl Conv<Quat> for Affine3 { #[inline(always)] fn conv(self) -> Quat { self.r.conv() } }
+
+impl Conv<A4<F4>> for Affine3 {
+    #[inline] fn conv(self) -> A4<F4> {
+        let t = self.t.unwrap_or(F3::ZERO);
+        let A3(r0, r1, r2) = self.r.rows();
+        A4(A4::a3a(r0, t.0), A4::a3a(r1, t.1), A4::a3a(r2, t.2), A4(0., 0., 0., 1.))
+    }
+}
+
 impl<A> Mul<A3<A>> for Affine3
     where A: Copy + Zero + Add<F, Output = A> + Add<Output = A>
            + Mul<F, Output = A>
@@ -52,3 +81,43 @@ impl Mul for Affine3 {
         Self::new(r, t)
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+    use crate::T;
+
+    const A: F3 = A3(0.3, 2., -1.);
+
+    #[test] fn inverse() {
+        let t = Affine3::translate(A3(1., -2., 3.)) * Affine3::rotate(A3(1., 1., 0.), 35.)
+              * Affine3::scale(A3(2., -0.5, 1.5));
+        let i = t.inv().unwrap();
+        assert!(close(i * (t * A), A));
+        assert!(close(t * (i * A), A));
+        assert_eq!(Affine3::scale(A3(0., 1., 1.)).inv(), Err(SingularMatrix));
+    }
+
+    #[test] fn composition() {
+        // rotation and non-uniform scale do not commute, so a transposed
+        // product would show up here
+        let a = Affine3::translate(A3(1., -2., 3.)) * Affine3::rotate(A3(1., 1., 0.), 35.);
+        let b = Affine3::scale(A3(2., -0.5, 1.5)) * Affine3::rotate(A3(0., 1., -1.), 70.);
+        assert!(close((a * b) * A, a * (b * A)));
+        assert!(close((b * a) * A, b * (a * A)));
+        let (a, b) = (T::rotate(A3(1., 1., 0.), 35.), T::scale(A3(2., -0.5, 1.5)));
+        assert!(close(((a * b) * P(A)).0, (a * (b * P(A))).0));
+        assert!(close(((a * b) / P(A)).0, (b / (a / P(A))).0));
+    }
+
+    #[test] fn look_at_inverse() {
+        let (pos, target) = (P(A3(1., -2., 0.5)), P(A3(10., 4., -10.)));
+        let t = T::look_at(pos, target, V(A3(0., 1., 1.))).unwrap();
+        assert!(close((t / pos).0, F3::ZERO));
+        assert!(close((t / (t * P(A))).0, A));
+        assert!(close(conv!(t / (target - pos) => N => F3), A3(0., 0., 1.)));
+        assert!(T::look_at(pos, target, target - pos).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use super::*;
+use rotscale3::RotScale3;
+
+const BBOX_STEPS: I = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Key {
+    time: F,
+    t:    F3,
+    r:    Quat,
+    s:    RotScale3,
+}
+
+// Keyframed transform interpolated per component: translation linearly,
+// rotation by slerp and stretch linearly (Shoemake and Duff)
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimatedTransform {
+    keys: Vec<Key>,
+}
+
+impl AnimatedTransform {
+    #[inline] pub fn new(t0: F, a: T, t1: F, b: T) -> Option<Self>
+    { Self::keyframed(vec![(t0, a), (t1, b)]) }
+
+    #[inline] pub fn fixed(t: T) -> Option<Self> { Self::keyframed(vec![(0., t)]) }
+
+    // `None` if there are no keyframes, any of them is singular, or
+    // consecutive ones differ in handedness (their stretches would blend
+    // through a singular one)
+    pub fn keyframed(mut keys: Vec<(F, T)>) -> Option<Self> {
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let keys = keys.into_iter().map(|(time, tr)| {
+            tr.f.decompose().map(|(t, r, s)| Key { time, t, r, s })
+        }).collect::<Option<Vec<_>>>()?;
+        if keys.is_empty() || keys.windows(2).any(|k| k[0].s.det() * k[1].s.det() <= 0.) {
+            return None
+        }
+        Some(Self { keys })
+    }
+
+    #[inline(always)] pub const fn is_animated(&self) -> bool { self.keys.len() > 1 }
+
+    // Time is clamped to the keyframe range
+    #[inline] pub fn at(&self, time: F) -> T {
+        let i = self.keys.partition_point(|k| k.time <= time);
+        if i == 0 { return self.keys[0].conv() }
+        if i == self.keys.len() { return self.keys[i - 1].conv() }
+        let (k0, k1) = (&self.keys[i - 1], &self.keys[i]);
+        k0.lerp(k1, (time - k0.time) / (k1.time - k0.time)).conv()
+    }
+
+    #[inline(always)] pub fn apply<A>(&self, time: F, a: A) -> <T as Mul<A>>::Output
+        where T: Mul<A>
+    { self.at(time) * a }
+
+    #[inline(always)] pub fn apply_inv<A>(&self, time: F, a: A) -> <T as Div<A>>::Output
+        where T: Div<A>
+    { self.at(time) / a }
+
+    // Conservative bounds of `b` over the whole animation: each segment is
+    // sampled and the samples are padded by the largest distance any corner
+    // can travel between them
+    pub fn motion_bbox(&self, b: &BBox) -> BBox {
+        if !self.is_animated() { return conv!(self.keys[0] => T) * *b }
+        self.keys.windows(2).fold(BBox::ZERO, |acc, k| acc | k[0].motion_bbox(&k[1], b))
+    }
+}
+
+impl Key {
+    #[inline] fn lerp(&self, k: &Self, u: F) -> Self {
+        Self { time: LinearScale::interp(A2(self.time, k.time), u),
+               t:    LinearScale::interp(A2(self.t, k.t), u),
+               r:    self.r.slerp(k.r, u),
+               s:    RotScale3::lerp(self.s, k.s, u) }
+    }
+
+    fn motion_bbox(&self, k: &Self, b: &BBox) -> BBox {
+        let theta = 2. * F::acos(self.r.dot(k.r).abs().min(1.));
+        let dt = V(k.t - self.t).norm();
+        let speed = b.corners().iter().map(|p| {
+            let (s0, s1) = (V(self.s * p.0), V(k.s * p.0));
+            theta.mul_add(s0.norm().max(s1.norm()), dt + (s1 - s0).norm())
+        }).fold(0., F::max);
+        let pad = speed * 0.5 / F::of(BBOX_STEPS);
+        (0..=BBOX_STEPS).fold(BBox::ZERO, |acc, i| {
+            let t: T = self.lerp(k, F::of(i) / F::of(BBOX_STEPS)).conv();
+            acc | (t * *b).expand(pad)
+        })
+    }
+}
+
+impl Conv<T> for Key {
+    #[inline] fn conv(self) -> T {
+        let m = conv!(self.r => RotScale3) * self.s;
+        // stretches are definite, so blends of keys of the same handedness
+        // (checked on construction) are too
+        let mi = m.inv().expect("blended keyframe stretch is singular");
+        T::new(T3::new(m, Some(self.t)), T3::new(mi, Some(-(mi * self.t))))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    const AXIS: F3 = A3(1., -2., 0.5);
+    const A: P = P(A3(0.3, 2., -1.));
+
+    fn keys() -> [T; 3] {
+        [T::translate(A3(1., 2., 3.)) * T::rotate(AXIS, 20.) * T::scale(A3(1., 2., 0.5)),
+         T::translate(A3(-2., 0., 1.)) * T::rotate(AXIS, 100.) * T::scale(A3(2., 1., 1.)),
+         T::translate(A3(0., 4., 1.)) * T::rotate(A3(0., 1., 0.), -60.)]
+    }
+
+    #[test] fn keyframes_round_trip() {
+        let k = keys();
+        let a = AnimatedTransform::keyframed(vec![(1., k[1]), (0., k[0]), (3., k[2])])
+                                  .unwrap();
+        for &(time, t) in &[(0., k[0]), (1., k[1]), (3., k[2]), (-1., k[0]), (5., k[2])] {
+            assert!(close_within(a.apply(time, A).0, (t * A).0, 1e-3));
+            assert!(close_within(a.apply_inv(time, A).0, (t / A).0, 1e-3));
+        }
+    }
+
+    #[test] fn interpolation() {
+        let a = AnimatedTransform::new(0., T::translate(A3(0., 0., 0.)),
+                                       2., T::translate(A3(2., 4., -2.)) * T::rotate(AXIS, 90.))
+                                  .unwrap();
+        let t = T::translate(A3(1., 2., -1.)) * T::rotate(AXIS, 45.);
+        assert!(close_within(a.apply(1., A).0, (t * A).0, 1e-3));
+        assert!(close_within(a.apply_inv(1., a.apply(1., A)).0, A.0, 1e-3));
+    }
+
+    #[test] fn singular() {
+        assert!(AnimatedTransform::fixed(T::scale(A3(1., 0., 1.))).is_none());
+        assert!(AnimatedTransform::keyframed(vec![]).is_none());
+        let mirror = T::scale(A3(-1., 1., 1.));
+        assert!(AnimatedTransform::new(0., T::ONE, 1., mirror).is_none());
+        let a = AnimatedTransform::new(0., mirror, 1., mirror * T::scale(A3(1., 3., 1.)))
+                                  .unwrap();
+        assert!(close_within(a.apply(0.5, A).0, A3(-0.3, 4., -1.), 1e-3));
+        assert!(close_within(a.apply_inv(0.5, a.apply(0.5, A)).0, A.0, 1e-3));
+    }
+
+    #[test] fn motion_bbox() {
+        let b = BBox::ZERO | P(A3(-1., 0., -0.5)) | P(A3(1., 2., 0.5));
+        let k = keys();
+        let a = AnimatedTransform::keyframed(vec![(0., k[0]), (1., k[1]), (3., k[2])])
+                                  .unwrap();
+        let mb = a.motion_bbox(&b);
+        for i in 0..=300 {
+            let t = a.at(F::of(i) * 0.01);
+            assert!(b.corners().iter().all(|&p| mb.contains(t * p)));
+        }
+        let s = AnimatedTransform::fixed(k[0]).unwrap();
+        let (sb, kb) = (s.motion_bbox(&b), k[0] * b);
+        assert!(close_within(sb.lower(), kb.lower(), 1e-3));
+        assert!(close_within(sb.upper(), kb.upper(), 1e-3));
+    }
+}
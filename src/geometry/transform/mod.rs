@@ -1,19 +1,39 @@
 mod affine3;
+mod animated;
 mod normalization2;
+mod projective4;
+mod quaternion;
 mod rotscale3;
 
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
 use std::ops::{Add, Div, Mul, Neg};
 
 use super::*;
+use crate::conv;
 
+pub use animated::AnimatedTransform;
 pub use normalization2::Norm2 as T2;
+pub use projective4::Projective4;
+pub use quaternion::Quat;
 pub use Affine3 as T;
 
 type T3 = affine3::Affine3;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SingularMatrix;
+
+impl fmt::Display for SingularMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+    { f.write_str("transform matrix is singular") }
+}
+
+impl Error for SingularMatrix {}
+
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 #[cfg_attr(feature="serde-derive", derive(Deserialize))]
-#[cfg_attr(feature="serde-derive", serde(from="Affine3Config"))]
+#[cfg_attr(feature="serde-derive", serde(try_from="Affine3Config"))]
 #[repr(C)]
 pub struct Affine3 {
     f: T3,
@@ -37,8 +57,10 @@ impl Affine3 {
         where A: Copy + Conv<F3>
     { Self::new(T3::rotate(axis, angle), T3::rotate(axis, -angle)) }
 
-    #[inline(always)] pub fn look_at(pos: P, target: P, up: V) -> Self
-    { Self::new(T3::look_at(pos, target, up), T3::ONE) }
+    /// # Errors
+    /// Fails if `up` is parallel to the view direction or `pos` equals `target`
+    #[inline(always)] pub fn look_at(pos: P, target: P, up: V) -> Result<Self, SingularMatrix>
+    { Self::try_from(T3::look_at(pos, target, up)) }
 
     #[inline(always)] pub fn from_frame<A: Conv<F3>>(v: A) -> Self {
         let t = T3::from_frame(v);
@@ -54,11 +76,24 @@ impl Affine3 {
     { it.rfold(Self::ONE, Mul::mul) }
 }
 
+impl TryFrom<T3> for Affine3 {
+    type Error = SingularMatrix;
+    #[inline(always)] fn try_from(f: T3) -> Result<Self, SingularMatrix>
+    { Ok(Self::new(f, f.inv()?)) }
+}
+
 impl Inv for Affine3 {
     type Output = Self;
     #[inline(always)] fn inv(self) -> Self { Self::new(self.i, self.f) }
 }
 
+impl Conv<Affine3> for Quat {
+    #[inline(always)] fn conv(self) -> Affine3
+    { Affine3::new(conv!(self => T3), conv!(self.conj() => T3)) }
+}
+
+impl Conv<Quat> for Affine3 { #[inline(always)] fn conv(self) -> Quat { self.f.conv() } }
+
 impl Mul for Affine3 {
     type Output = Self;
     #[inline(always)] fn mul(self, s: Self) -> Self
@@ -100,13 +135,14 @@ enum Affine3Config {
 }
 
 #[cfg(feature="serde-derive")]
-impl From<Affine3Config> for Affine3 {
-    fn from(tc: Affine3Config) -> Self {
+impl TryFrom<Affine3Config> for Affine3 {
+    type Error = SingularMatrix;
+    fn try_from(tc: Affine3Config) -> Result<Self, SingularMatrix> {
         match tc {
-            Affine3Config::Translate(v) => Self::translate(v),
-            Affine3Config::Scale(s) => Self::scale(s),
+            Affine3Config::Translate(v) => Ok(Self::translate(v)),
+            Affine3Config::Scale(s) => Self::try_from(T3::scale(s)),
             Affine3Config::Rotate { axis, angle }
-                => Self::rotate(axis, angle),
+                => Ok(Self::rotate(axis, angle)),
             Affine3Config::LookAt { pos, target, up }
                 => Self::look_at(pos, target, up),
         }
@@ -144,6 +180,12 @@ mod tests {
         assert_eq!(serde_json::from_str::<Affine3>(s).unwrap(),
                    Affine3::look_at(P(A3(1., -2., 0.5)),
                                            P(A3(100., 4., -10.)),
-                                           V(A3(0., 1., 1.))));
+                                           V(A3(0., 1., 1.))).unwrap());
+    }
+
+    #[test] fn deser_singular() {
+        let s = r#"{ "look_at": { "pos": [0, 0, 0], "target": [0, 2, 0], "up": [0, 1, 0] } }"#;
+        assert!(serde_json::from_str::<Affine3>(s).is_err());
+        assert!(serde_json::from_str::<Affine3>(r#"{ "scale": [1, 0, 2] }"#).is_err());
     }
 }
@@ -15,6 +15,25 @@ impl Norm2 {
     #[inline(always)] const fn new(s: F2, t: F2) -> Self { Self { s, t } }
     #[inline(always)] pub fn translate(t: F2) -> Self { Self::new(F2::ONE, t) }
     #[inline(always)] pub fn scale(s: F2) -> Self { Self::new(s, F2::ZERO) }
+
+    // Maps the screen window to [0, res] with y pointing down
+    #[inline] pub fn screen_to_raster(res: F2, lower: F2, upper: F2) -> Self {
+        Self::scale(res / A2(upper[0] - lower[0], lower[1] - upper[1]))
+            * Self::translate(-A2(lower[0], upper[1]))
+    }
+}
+
+impl Inv for Norm2 {
+    type Output = Self;
+    #[inline(always)] fn inv(self) -> Self { let s = self.s.inv(); Self::new(s, -self.t * s) }
+}
+
+impl Conv<Projective4> for Norm2 {
+    #[inline] fn conv(self) -> Projective4 {
+        let rows = |n: Self| A4(A4(n.s[0], 0., 0., n.t[0]), A4(0., n.s[1], 0., n.t[1]),
+                                A4(0., 0., 1., 0.), A4(0., 0., 0., 1.));
+        Projective4::new(rows(self), rows(self.inv()))
+    }
 }
 
 impl Mul for Norm2 {
@@ -27,3 +46,8 @@ impl Mul<F2> for Norm2 {
     type Output = F2;
     #[inline(always)] fn mul(self, o: F2) -> F2 { self.s * o + self.t }
 }
+
+impl Div<F2> for Norm2 {
+    type Output = F2;
+    #[inline(always)] fn div(self, o: F2) -> F2 { (o - self.t) / self.s }
+}
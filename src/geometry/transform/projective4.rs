@@ -0,0 +1,143 @@
+use super::*;
+
+type M4 = A4<F4>;
+
+const IDENTITY: M4 = A4(A4(1., 0., 0., 0.), A4(0., 1., 0., 0.),
+                        A4(0., 0., 1., 0.), A4(0., 0., 0., 1.));
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(C)]
+pub struct Projective4 {
+    f: M4,
+    i: M4,
+}
+
+impl One for Projective4 { const ONE: Self = Self::new(IDENTITY, IDENTITY); }
+impl Default for Projective4 { #[inline(always)] fn default() -> Self { Self::ONE } }
+
+impl Projective4 {
+    #[inline(always)] pub(super) const fn new(f: M4, i: M4) -> Self { Self { f, i } }
+
+    // Maps the frustum with the given field of view (in degrees) to x, y in
+    // [-1, 1] at z in [0, 1] between the near and far planes
+    #[inline] pub fn perspective(fov: F, near: F, far: F) -> Self {
+        let s = (fov * 0.5).tand().inv();
+        let (a, b) = (far / (far - near), -far * near / (far - near));
+        Self::new(A4(A4(s, 0., 0., 0.), A4(0., s, 0., 0.),
+                     A4(0., 0., a, b), A4(0., 0., 1., 0.)),
+                  A4(A4(s.inv(), 0., 0., 0.), A4(0., s.inv(), 0., 0.),
+                     A4(0., 0., 0., 1.), A4(0., 0., b.inv(), -a / b)))
+    }
+
+    // Maps z in [near, far] to [0, 1]
+    #[inline] pub fn orthographic(near: F, far: F) -> Self {
+        let d = far - near;
+        Self::new(A4(A4(1., 0., 0., 0.), A4(0., 1., 0., 0.),
+                     A4(0., 0., d.inv(), -near / d), A4(0., 0., 0., 1.)),
+                  A4(A4(1., 0., 0., 0.), A4(0., 1., 0., 0.),
+                     A4(0., 0., d, near), A4(0., 0., 0., 1.)))
+    }
+
+    // With `self` mapping camera space to raster space, the camera-space ray
+    // from the near plane through the given raster position
+    #[inline] pub fn raster_ray(&self, raster: F2) -> R {
+        let near = *self / P(A3::a2a(raster, 0.));
+        let far = *self / P(A3::a2a(raster, 1.));
+        R::unbounded(near, far - near)
+    }
+}
+
+#[inline(always)] fn apply(m: M4, h: F4) -> F4 { m.map(|row| F4::dot(row, h)) }
+
+#[inline(always)] fn product(a: M4, b: M4) -> M4
+{ a.map(|r| b.0 * r.0 + b.1 * r.1 + b.2 * r.2 + b.3 * r.3) }
+
+#[inline(always)] fn project(h: F4) -> P { P(h.xyz() / h.3) }
+
+impl Inv for Projective4 {
+    type Output = Self;
+    #[inline(always)] fn inv(self) -> Self { Self::new(self.i, self.f) }
+}
+
+impl Conv<Projective4> for T {
+    #[inline(always)] fn conv(self) -> Projective4
+    { Projective4::new(self.f.conv(), self.i.conv()) }
+}
+
+impl Mul for Projective4 {
+    type Output = Self;
+    #[inline(always)] fn mul(self, o: Self) -> Self
+    { Self::new(product(self.f, o.f), product(o.i, self.i)) }
+}
+
+impl Mul<T> for Projective4 {
+    type Output = Self;
+    #[inline(always)] fn mul(self, t: T) -> Self { self * conv!(t => Self) }
+}
+
+impl Mul<Projective4> for T {
+    type Output = Projective4;
+    #[inline(always)] fn mul(self, p: Projective4) -> Projective4
+    { conv!(self => Projective4) * p }
+}
+
+impl Mul<F4> for Projective4
+{ type Output = F4; #[inline(always)] fn mul(self, h: F4) -> F4 { apply(self.f, h) } }
+
+impl Div<F4> for Projective4
+{ type Output = F4; #[inline(always)] fn div(self, h: F4) -> F4 { apply(self.i, h) } }
+
+impl Mul<P> for Projective4 {
+    type Output = P;
+    #[inline(always)] fn mul(self, p: P) -> P { project(self * A4::a3a(p.0, 1.)) }
+}
+
+impl Div<P> for Projective4 {
+    type Output = P;
+    #[inline(always)] fn div(self, p: P) -> P { project(self / A4::a3a(p.0, 1.)) }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    const A: P = P(A3(0.3, 2., 5.));
+
+    #[test] fn perspective() {
+        let p = Projective4::perspective(90., 1., 10.);
+        assert!(close_within(p * P(A3(1., -1., 1.)), P(A3(1., -1., 0.)), 1e-3));
+        assert!(close_within(p * P(A3(-10., 5., 10.)), P(A3(-1., 0.5, 1.)), 1e-3));
+        assert!(close_within(p / (p * A), A, 1e-3));
+        assert!(close_within(p.inv() * (p * A), A, 1e-3));
+    }
+
+    #[test] fn orthographic() {
+        let p = Projective4::orthographic(2., 6.);
+        assert!(close_within(p * A, P(A3(0.3, 2., 0.75)), 1e-3));
+        assert!(close_within(p / (p * A), A, 1e-3));
+    }
+
+    #[test] fn composition() {
+        let t = T::translate(A3(1., -2., 3.)) * T::rotate(A3(1., 1., 0.), 30.);
+        let p = Projective4::perspective(60., 0.5, 100.);
+        assert!(close_within((p * t) * A, p * (t * A), 1e-3));
+        assert!(close_within((p * t) / (p * (t * A)), A, 1e-3));
+        assert!(close_within((t * p) * A, t * (p * A), 1e-3));
+    }
+
+    #[test] fn raster_ray() {
+        let res = A2(200., 100.);
+        let s: Projective4 = T2::screen_to_raster(res, A2(-2., -1.), A2(2., 1.)).conv();
+        let p = s * Projective4::perspective(90., 1., 100.);
+        let r = p.raster_ray(res * 0.5);
+        assert!(close_within(r.o, P(A3(0., 0., 1.)), 1e-3));
+        assert!(close_within(r.d, V(A3(0., 0., 1.)), 1e-3));
+        let r = p.raster_ray(A2(200., 0.));
+        assert!(close_within(r.d, V(A3(2., 1., 1.) / F::sqrt(6.)), 1e-3));
+        assert!(close_within(r.o, P(A3(2., 1., 1.)), 1e-3));
+        let o = s * Projective4::orthographic(0., 10.);
+        assert!(close_within(o.raster_ray(A2(0., 100.)).o, P(A3(-2., -1., 0.)), 1e-3));
+    }
+}
@@ -0,0 +1,173 @@
+use std::ops::Sub;
+
+use super::*;
+use crate::{conv, op};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+#[repr(C)]
+pub struct Quat {
+    pub v: F3,
+    pub w: F,
+}
+
+impl Zero for Quat { const ZERO: Self = Self::new(F3::ZERO, 0.); }
+impl One for Quat { const ONE: Self = Self::new(F3::ZERO, 1.); }
+impl Default for Quat { #[inline(always)] fn default() -> Self { Self::ONE } }
+
+impl Quat {
+    #[inline(always)] pub const fn new(v: F3, w: F) -> Self { Self { v, w } }
+
+    #[inline(always)] pub fn rotate<A: Conv<F3>>(axis: A, angle: F) -> Self {
+        let half = angle * 0.5;
+        Self::new(conv!(axis.conv() => N => F3) * half.sind(), half.cosd())
+    }
+
+    // Rotation about x, then y, then z (in degrees)
+    #[inline] pub fn euler(angles: F3) -> Self {
+        XYZ.zip(angles, |dim, angle| Self::rotate(F3::basis(dim), angle))
+           .fold(Self::ONE, |q, r| r * q)
+    }
+
+    #[inline(always)] pub fn dot(self, q: Self) -> F { self.w.mul_add(q.w, F3::dot(self.v, q.v)) }
+    #[inline(always)] pub fn norm(self) -> F { self.dot(self).sqrt() }
+    #[must_use]
+    #[inline(always)] pub fn unit(self) -> Self { self * self.norm().inv() }
+    #[must_use]
+    #[inline(always)] pub fn conj(self) -> Self { Self::new(-self.v, self.w) }
+
+    #[inline] pub fn axis_angle(self) -> (F3, F) {
+        let q = if self.w < 0. { -self.unit() } else { self.unit() };
+        let st = F3::dot(q.v, q.v).sqrt();
+        let axis = if st > 0. { q.v / st } else { F3::basis(Z) };
+        (axis, 2. * F::atan2(st, q.w).to_degrees())
+    }
+
+    #[must_use]
+    #[inline] pub fn nlerp(self, q: Self, t: F) -> Self {
+        let q = if self.dot(q) < 0. { -q } else { q };
+        LinearScale::interp(A2(self, q), t).unit()
+    }
+
+    #[must_use]
+    #[inline] pub fn slerp(self, q: Self, t: F) -> Self {
+        let ct = self.dot(q);
+        let (q, ct) = if ct < 0. { (-q, -ct) } else { (q, ct) };
+        if ct > 0.9995 { return self.nlerp(q, t) }
+        let theta = F::acos(ct) * t;
+        let perp = (q - self * ct).unit();
+        self * theta.cos() + perp * theta.sin()
+    }
+
+    #[inline(always)] fn rot(self, a: F3) -> F3 {
+        let (u, a) = (V(self.v), V(a));
+        let uv = u * a;
+        (a + uv * (2. * self.w) + u * uv * 2.).0
+    }
+}
+
+op!(Mul::mul, Quat -> *P -> P);
+op!(Mul::mul, Quat -> *V -> V);
+
+impl Neg for Quat
+{ type Output = Self; #[inline(always)] fn neg(self) -> Self { Self::new(-self.v, -self.w) } }
+
+impl Add for Quat {
+    type Output = Self;
+    #[inline(always)] fn add(self, q: Self) -> Self { Self::new(self.v + q.v, self.w + q.w) }
+}
+
+impl Sub for Quat {
+    type Output = Self;
+    #[inline(always)] fn sub(self, q: Self) -> Self { Self::new(self.v - q.v, self.w - q.w) }
+}
+
+impl Mul<F> for Quat {
+    type Output = Self;
+    #[inline(always)] fn mul(self, f: F) -> Self { Self::new(self.v * f, self.w * f) }
+}
+
+impl Mul for Quat {
+    type Output = Self;
+    #[inline(always)] fn mul(self, q: Self) -> Self {
+        let v = q.v * self.w + self.v * q.w + (V(self.v) * V(q.v)).0;
+        Self::new(v, self.w.mul_add(q.w, -F3::dot(self.v, q.v)))
+    }
+}
+
+impl Mul<F3> for Quat
+{ type Output = F3; #[inline(always)] fn mul(self, a: F3) -> F3 { self.rot(a) } }
+
+impl Mul<N> for Quat
+{ type Output = N; #[inline(always)] fn mul(self, n: N) -> N { N(self * n.0) } }
+
+impl Inv for Quat {
+    type Output = Self;
+    #[inline(always)] fn inv(self) -> Self { self.conj() * self.dot(self).inv() }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    fn qclose(a: Quat, b: Quat) -> bool
+    { close(a.v, b.v) && (a.w - b.w).abs() < F::EPS }
+
+    const AXIS: F3 = A3(1., -2., 0.5);
+    const A: F3 = A3(0.3, 2., -1.);
+
+    #[test] fn matches_rotscale() {
+        let q = Quat::rotate(AXIS, 50.);
+        assert!(close(q * A, Affine3::rotate(AXIS, 50.) * A));
+        assert!(close(q.inv() * A, Affine3::rotate(AXIS, 50.) / A));
+    }
+
+    #[test] fn matrix_round_trip() {
+        for &angle in &[0., 30., 90., 179., 180., 270.] {
+            let q = Quat::rotate(AXIS, angle);
+            let t: Affine3 = q.conv();
+            let p: Quat = t.conv();
+            assert!(qclose(p, q) || qclose(p, -q));
+            assert!(close(t * A, q * A));
+            assert!(close(t / A, q.conj() * A));
+        }
+    }
+
+    #[test] fn composition() {
+        let q = Quat::rotate(AXIS, 70.);
+        let t = (Affine3::rotate(AXIS, 70.), Affine3::rotate(AXIS + A, -20.));
+        let r = Quat::rotate(AXIS + A, -20.);
+        assert!(close((q * r) * A, t.0 * (t.1 * A)));
+        assert!(qclose(q * q.inv(), Quat::ONE));
+    }
+
+    #[test] fn euler() {
+        let a = Affine3::rotate(A3(1., 0., 0.), 60.) * A;
+        let a = Affine3::rotate(A3(0., 1., 0.), -45.) * a;
+        let a = Affine3::rotate(A3(0., 0., 1.), 30.) * a;
+        assert!(close(Quat::euler(A3(60., -45., 30.)) * A, a));
+    }
+
+    #[test] fn axis_angle() {
+        let (axis, angle) = Quat::rotate(AXIS, 120.).axis_angle();
+        assert!(close(axis, conv!(AXIS => N => F3)));
+        assert!((angle - 120.).abs() < 1e-2);
+    }
+
+    #[test] fn slerp() {
+        let (q, r) = (Quat::rotate(AXIS, 10.), Quat::rotate(AXIS, 130.));
+        assert!(qclose(q.slerp(r, 0.), q));
+        assert!(qclose(q.slerp(r, 1.), r));
+        assert!(qclose(q.slerp(r, 0.25), Quat::rotate(AXIS, 40.)));
+        assert!(qclose(q.slerp(-r, 0.25), Quat::rotate(AXIS, 40.)));
+        assert!(qclose(q.nlerp(r, 0.5), Quat::rotate(AXIS, 70.)));
+    }
+
+    #[test] fn accumulate() {
+        let step = Quat::rotate(AXIS, 0.1);
+        let q = (0..3600).fold(Quat::ONE, |q, _| (step * q).unit());
+        assert!(qclose(q, Quat::ONE) || qclose(q, -Quat::ONE));
+    }
+}
@@ -52,6 +52,37 @@ impl RotScale3 {
         Self::from_cols(conv!(v2 => F3), conv!(v * v2 => F3), conv!(v => F3))
     }
 
+    #[inline(always)] pub(super) fn rows(&self) -> A3<F3>
+    { self.0.unwrap_or_else(|| XYZ.map(F3::basis)) }
+
+    #[inline] pub fn det(&self) -> F
+    { self.0.map_or(1., |A3(r0, r1, r2)| F3::dot(r0, cross(r1, r2))) }
+
+    // Adjugate over determinant; `None` if the matrix is singular
+    #[inline] pub fn inv(&self) -> Option<Self> {
+        let Some(A3(r0, r1, r2)) = self.0 else { return Some(*self) };
+        let det = self.det();
+        if det == 0. || !det.is_finite() { return None }
+        Some(Self::from_cols(cross(r1, r2), cross(r2, r0), cross(r0, r1)) * det.inv())
+    }
+
+    #[inline] pub fn lerp(a: Self, b: Self, t: F) -> Self
+    { Self(Some(LinearScale::interp(A2(a.rows(), b.rows()), t))) }
+
+    // Polar decomposition M = R S into a rotation R and a symmetric stretch S
+    // (Shoemake and Duff, "Matrix Animation and Polar Decomposition")
+    #[inline] pub fn polar(&self) -> Option<(Self, Self)> {
+        let mut r = *self;
+        for _ in 0..100 {
+            let next = Self::lerp(r, r.inv()?.t(), 0.5);
+            let diff = (next.rows() - r.rows()).map(|row| row.map(F::abs).max()).max();
+            r = next;
+            if diff < F::EPS * F::EPS { break }
+        }
+        let r = if r.det() < 0. { r * -1. } else { r };
+        Some((r, r.t() * *self))
+    }
+
     #[inline] pub fn t(&self) -> Self {
         self.0.map_or_else(|| *self, |m|
             Self::from_rows(A3(m[0_usize][0], m[1_usize][0], m[2_usize][0]),
@@ -61,6 +92,45 @@ impl RotScale3 {
     }
 }
 
+impl Conv<RotScale3> for Quat {
+    #[inline] fn conv(self) -> RotScale3 {
+        let Quat { v: A3(x, y, z), w } = self;
+        RotScale3::from_rows(A3(F::of(-2.).mul_add(y.mul_add(y, z.sq()), 1.),
+                                2. * x.mul_add(y, -z * w),
+                                2. * x.mul_add(z, y * w)),
+                             A3(2. * x.mul_add(y, z * w),
+                                F::of(-2.).mul_add(x.mul_add(x, z.sq()), 1.),
+                                2. * y.mul_add(z, -x * w)),
+                             A3(2. * x.mul_add(z, -y * w),
+                                2. * y.mul_add(z, x * w),
+                                F::of(-2.).mul_add(x.mul_add(x, y.sq()), 1.)))
+    }
+}
+
+// Assumes a pure rotation
+impl Conv<Quat> for RotScale3 {
+    #[inline] fn conv(self) -> Quat {
+        self.0.map_or(Quat::ONE, |m| {
+            let A3(A3(m00, m01, m02), A3(m10, m11, m12), A3(m20, m21, m22)) = m;
+            let tr = m00 + m11 + m22;
+            let q = if tr > 0. {
+                let s = 2. * F::sqrt(tr + 1.);
+                Quat::new(A3(m21 - m12, m02 - m20, m10 - m01) / s, 0.25 * s)
+            } else if m00 > m11 && m00 > m22 {
+                let s = 2. * F::sqrt(1. + m00 - m11 - m22);
+                Quat::new(A3(0.25 * s, (m01 + m10) / s, (m02 + m20) / s), (m21 - m12) / s)
+            } else if m11 > m22 {
+                let s = 2. * F::sqrt(1. + m11 - m00 - m22);
+                Quat::new(A3((m01 + m10) / s, 0.25 * s, (m12 + m21) / s), (m02 - m20) / s)
+            } else {
+                let s = 2. * F::sqrt(1. + m22 - m00 - m11);
+                Quat::new(A3((m02 + m20) / s, (m12 + m21) / s, 0.25 * s), (m10 - m01) / s)
+            };
+            q.unit()
+        })
+    }
+}
+
 impl<A> Mul<A3<A>> for RotScale3
     where A: Copy + Zero + Add<Output = A> + Mul<F, Output = A>
 {
@@ -72,5 +142,47 @@ impl<A> Mul<A3<A>> for RotScale3
 impl Mul for RotScale3 {
     type Output = Self;
     #[inline(always)] fn mul(self, o: Self) -> Self
-    { self.t().0.map_or_else(|| o, |m| Self(Some(o * m)).t()) }
+    { o.0.map_or(self, |m| Self(Some(self * m))) }
+}
+
+impl Mul<F> for RotScale3 {
+    type Output = Self;
+    #[inline(always)] fn mul(self, f: F) -> Self { Self(Some(self.rows() * f)) }
+}
+
+#[inline(always)] fn cross(a: F3, b: F3) -> F3 { (V(a) * V(b)).0 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+
+    impl Diff for RotScale3 {
+        fn diff(self, b: Self) -> F
+        { (self.rows() - b.rows()).map(|r| r.map(F::abs).max()).max() }
+    }
+
+    const A: F3 = A3(0.3, 2., -1.);
+
+    fn m() -> RotScale3
+    { RotScale3::rotate(A3(1., -2., 0.5), 40.) * RotScale3::scale(A3(2., 0.5, -1.)) }
+
+    #[test] fn product() {
+        let (a, b) = (RotScale3::rotate(A3(0., 1., 1.), 30.), m());
+        assert!((((a * b) * A) - (a * (b * A))).map(F::abs).max() < F::EPS);
+    }
+
+    #[test] fn inverse() {
+        assert!(close(m() * m().inv().unwrap(), RotScale3::ONE));
+        assert!(RotScale3::scale(A3(1., 0., 2.)).inv().is_none());
+    }
+
+    #[test] fn polar() {
+        let (r, s) = m().polar().unwrap();
+        assert!(close(r * s, m()));
+        assert!(close(r * r.t(), RotScale3::ONE));
+        assert!(close(s, s.t()));
+        assert!(r.det() > 0.);
+    }
 }
@@ -10,16 +10,59 @@ pub struct V(pub(crate) F3);
 
 impl Zero for V { const ZERO: Self = V(F3::ZERO); }
 
+pub trait Dot<B = Self>: Sized {
+    fn dot(self, b: B) -> F;
+    #[inline(always)] fn abs_dot(self, b: B) -> F { self.dot(b).abs() }
+}
+
 impl V {
-    #[inline(always)] pub fn norm2(self) -> F { F3::dot(self.0, self.0) }
+    #[inline(always)] pub fn norm2(self) -> F { self.dot(self) }
     #[inline(always)] pub fn norm(self) -> F { self.norm2().sqrt() }
+    #[must_use]
     #[inline(always)] pub fn unit(self) -> V { self / self.norm() }
 
-    #[inline(always)] fn cross(self, v: V) -> V
+    #[must_use]
+    #[inline(always)] pub fn cross(self, v: V) -> V
     { V(self.0.swizzle(1, 2, 0) * v.0.swizzle(2, 0, 1)
       - self.0.swizzle(2, 0, 1) * v.0.swizzle(1, 2, 0)) }
+
+    #[must_use]
+    #[inline(always)] pub fn face_forward(self, n: N) -> V
+    { if self.dot(n) < 0. { -self } else { self } }
+
+    #[must_use]
+    #[inline(always)] pub fn min(self, v: V) -> V { V(self.0.zip(v.0, F::min)) }
+    #[must_use]
+    #[inline(always)] pub fn max(self, v: V) -> V { V(self.0.zip(v.0, F::max)) }
+    #[must_use]
+    #[inline(always)] pub fn abs(self) -> V { V(self.0.map(F::abs)) }
+
+    #[inline(always)] pub fn max_component(self) -> F { self.0.max() }
+
+    #[inline(always)] pub fn max_dimension(self) -> Dim {
+        let A3(x, y, z) = self.0;
+        if x > y { if x > z { X } else { Z } } else if y > z { Y } else { Z }
+    }
+
+    #[must_use]
+    #[inline(always)] pub fn permute(self, x: Dim, y: Dim, z: Dim) -> V
+    { V(A3(self[x], self[y], self[z])) }
+
+    // Orthonormal vectors completing a basis with this unit vector (Duff et
+    // al., "Building an Orthonormal Basis, Revisited")
+    #[inline] pub fn coordinate_system(self) -> (V, V) {
+        let A3(x, y, z) = self.0;
+        let sign = F::copysign(1., z);
+        let k = -(sign + z).inv();
+        let kxy = x * y * k;
+        (V(A3((sign * x.sq()).mul_add(k, 1.), sign * kxy, -sign * x)),
+         V(A3(kxy, y.sq().mul_add(k, sign), -y)))
+    }
 }
 
+impl Dot for V { #[inline(always)] fn dot(self, v: V) -> F { F3::dot(self.0, v.0) } }
+impl Dot<N> for V { #[inline(always)] fn dot(self, n: N) -> F { self.dot(n.0) } }
+
 op!(Neg::neg, *V);
 op!(Add::add, *V -> *V -> V);
 op!(Add::add, *P -> *V -> P);
@@ -52,11 +95,46 @@ impl Index<Dim> for V {
 }
 
 
-#[cfg(feature="serde-derive")]
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::*;
+
+    const A: V = V(A3(0.3, -2., 1.));
+
+    #[test] fn products() {
+        let b = V(A3(-1., 0.5, 2.));
+        let c = A.cross(b);
+        assert!(c.dot(A).abs() < F::EPS && c.dot(b).abs() < F::EPS);
+        assert!(close(c, A * b));
+        let n: N = b.conv();
+        assert!((A.dot(n) - n.dot(A)).abs() < F::EPS);
+        assert!((A.abs_dot(-n) - A.dot(n)).abs() < F::EPS);
+        assert!(close(A.face_forward(n), A) && close(A.face_forward(-n), -A));
+        assert_eq!(n.face_forward(-A), -n);
+    }
+
+    #[test] fn components() {
+        let b = V(A3(-1., 0.5, 2.));
+        assert_eq!(A.min(b), V(A3(-1., -2., 1.)));
+        assert_eq!(A.max(b), V(A3(0.3, 0.5, 2.)));
+        assert_eq!(A.abs().max_dimension(), Y);
+        assert!((A.abs().max_component() - 2.).abs() < F::EPS);
+        assert_eq!(A.permute(Z, X, Y), V(A3(1., 0.3, -2.)));
+    }
+
+    #[test] fn coordinate_system() {
+        for &v in &[A, -A, V(A3(0., 0., 1.)), V(A3(0., 0., -1.)), V(A3(1., 1e-8, -1e-8))] {
+            let v = v.unit();
+            let (s, t) = v.coordinate_system();
+            assert!((s.norm() - 1.).abs() < F::EPS && (t.norm() - 1.).abs() < F::EPS);
+            assert!(s.dot(v).abs() < F::EPS && t.dot(v).abs() < F::EPS
+                    && s.dot(t).abs() < F::EPS);
+            assert!(close(s * t, v));
+        }
+    }
 
+    #[cfg(feature="serde-derive")]
     #[test] fn deser_v() {
         assert_eq!(serde_json::from_str::<V>("[-1, 1, 0.5]").unwrap(),
                    V(A3(-1., 1., 0.5)));
@@ -24,10 +24,69 @@ pub struct UniformSphere;
 #[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
 pub struct UniformHemisphere;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct UniformCone;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct SphericalTriangle;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct SphericalRectangle;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct Bilinear;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
 pub struct BeckmannHemisphere;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct TrowbridgeReitzHemisphere;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct TrowbridgeReitzVisible;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct TrowbridgeReitzVisibleCap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct IsotropicPhase;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct HenyeyGreenstein;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct TwoLobeHenyeyGreenstein;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature="serde-derive", derive(Deserialize, Serialize))]
+pub struct Rayleigh;
+
+// Common interface over the warps below, mapping samples in [0, 1)^2 to
+// outputs distributed according to `pdf` under some parameters
+pub trait Warp {
+    type Sample;
+    type Output;
+    type Param: Copy;
+
+    fn warp(&self, s: Self::Sample, p: Self::Param) -> Self::Output;
+    fn pdf(&self, x: Self::Output, p: Self::Param) -> F;
+}
+
+pub trait InverseWarp: Warp {
+    fn inverse_warp(&self, x: Self::Output, p: Self::Param) -> Self::Sample;
+}
+
 impl UniformTriangle {
     #[inline(always)] pub fn warp(s: F2) -> F2 {
         let t = s * 0.5;
@@ -36,6 +95,10 @@ impl UniformTriangle {
         else { A2(t[0] - o, t[1]) }
     }
 
+    #[inline(always)] pub fn inverse_warp(p: F2) -> F2 {
+        if p[Y] > p[X] { A2(2. * p[X], p[X] + p[Y]) } else { A2(p[X] + p[Y], 2. * p[Y]) }
+    }
+
     #[inline(always)] pub const fn pdf() -> F { 2. }
 }
 
@@ -50,6 +113,20 @@ impl UniformDisk {
         }
     }
 
+    #[inline] pub fn inverse_warp(p: F2) -> F2 {
+        if p == F2::ZERO { return F2::HALF }
+        let r = F2::dot(p, p).sqrt();
+        let u = if F::abs(p[X]) >= F::abs(p[Y]) {
+            let r = r.copysign(p[X]);
+            A2(r, r * F::atan(p[Y] / p[X]) / F::FOURTH_PI)
+        } else {
+            let r = r.copysign(p[Y]);
+            let t = F::atan2(p[Y].abs(), p[X] * p[Y].signum());
+            A2(r * (F::HALF_PI - t) / F::FOURTH_PI, r)
+        };
+        (u + 1.) * 0.5
+    }
+
     #[inline(always)] pub const fn pdf() -> F { F::INV_PI }
 }
 
@@ -59,8 +136,13 @@ impl CosineHemisphere {
         F3::a2a(p, F::sqrt(1. - F2::dot(p, p)))
     }
 
+    #[inline(always)] pub fn inverse_warp<A: Conv<F3>>(v: A) -> F2 {
+        let v = v.conv();
+        UniformDisk::inverse_warp(A2(v[X], v[Y]))
+    }
+
     #[inline(always)] pub fn pdf<A: Conv<F3>>(s: A) -> F
-    { Frame::ct(s) * F::INV_PI }
+    { Frame::ct(s).max(0.) * F::INV_PI }
 }
 
 impl UniformCylinder {
@@ -69,6 +151,11 @@ impl UniformCylinder {
         A3(F::cos(t), F::sin(t), 2. * s[X] - 1.)
     }
 
+    #[inline(always)] pub fn inverse_warp<A: Conv<F3>>(v: A) -> F2 {
+        let v = v.conv();
+        A2((v[Z] + 1.) * 0.5, azimuth(v))
+    }
+
     #[inline(always)] pub const fn pdf() -> F { F::INV_4PI }
 }
 
@@ -79,6 +166,9 @@ impl UniformSphere {
         A3(r * v[X], r * v[Y], v[Z])
     }
 
+    #[inline(always)] pub fn inverse_warp<A: Conv<F3>>(v: A) -> F2
+    { UniformCylinder::inverse_warp(v) }
+
     #[inline(always)] pub const fn pdf() -> F { F::INV_4PI }
 }
 
@@ -88,9 +178,31 @@ impl UniformHemisphere {
         A3(v[X], v[Y], v[Z].abs())
     }
 
+    // The preimage in the upper half of the sphere
+    #[inline(always)] pub fn inverse_warp<A: Conv<F3>>(v: A) -> F2
+    { UniformSphere::inverse_warp(v) }
+
     #[inline(always)] pub const fn pdf() -> F { F::INV_2PI }
 }
 
+// Directions about the z-axis within `acos(ct_max)`
+impl UniformCone {
+    #[inline(always)] pub fn warp(s: F2, ct_max: F) -> F3 {
+        let ct = LinearScale::interp(A2(1., ct_max), s[X]);
+        let st = F::sqrt((1. - ct.sq()).max(0.));
+        let phi = F::TWO_PI * s[Y];
+        A3(st * phi.cos(), st * phi.sin(), ct)
+    }
+
+    #[inline(always)] pub fn inverse_warp<A: Conv<F3>>(v: A, ct_max: F) -> F2 {
+        let v = v.conv();
+        A2((1. - v[Z]) / (1. - ct_max), azimuth(v))
+    }
+
+    #[inline(always)] pub fn pdf<A: Conv<F3>>(s: A, ct_max: F) -> F
+    { if Frame::ct(s) >= ct_max { F::INV_2PI / (1. - ct_max) } else { 0. } }
+}
+
 impl BeckmannHemisphere {
     #[inline(always)] pub fn warp(s: F2, alpha: F) -> F3 {
         let c2t = (1. - alpha.sq() * F::ln(F::ONE - s[0])).inv();
@@ -99,10 +211,708 @@ impl BeckmannHemisphere {
         A3(r * F::cos(phi), r * F::sin(phi), F::sqrt(c2t))
     }
 
+    #[inline(always)] pub fn inverse_warp<A: Copy + Conv<F3>>(v: A, alpha: F) -> F2
+    { A2(1. - F::exp(-Frame::t2t(v) / alpha.sq()), azimuth(v.conv())) }
+
     #[inline(always)] pub fn pdf<A: Conv<F3>>(s: A, alpha: F) -> F {
         let a2_inv = alpha.sq().inv();
         let ct = Frame::ct(s);
+        if ct <= 0. { return 0. }
         (F::INV_PI * a2_inv * F::exp(-a2_inv * (ct.sq().inv() - 1.)))
         / (ct * ct.sq())
     }
 }
+
+impl TrowbridgeReitzHemisphere {
+    #[inline(always)] pub fn warp(s: F2, alpha: F) -> F3 {
+        let t2t = alpha.sq() * s[X] / (1. - s[X]);
+        Frame::spher2cart(A2(F::atan(t2t.sqrt()), F::TWO_PI * s[Y]))
+    }
+
+    #[inline(always)] pub fn inverse_warp<A: Copy + Conv<F3>>(v: A, alpha: F) -> F2 {
+        let t2t = Frame::t2t(v);
+        A2(t2t / (alpha.sq() + t2t), azimuth(v.conv()))
+    }
+
+    #[inline(always)] pub fn pdf<A: Copy + Conv<F3>>(s: A, alpha: F) -> F
+    { TrowbridgeReitz::new(alpha).pdf(s) }
+}
+
+// Visible normals for an outgoing direction in the upper hemisphere (Heitz,
+// "Sampling the GGX Distribution of Visible Normals")
+impl TrowbridgeReitzVisible {
+    #[inline] pub fn warp<A: Conv<F3>>(s: F2, wo: A, alpha: F2) -> F3 {
+        let vh = stretch(wo.conv(), alpha);
+        let len2 = vh.0[X].sq() + vh.0[Y].sq();
+        let t1 = if len2 > 0. { V(A3(-vh.0[Y], vh.0[X], 0.)) / len2.sqrt() }
+                 else { V(A3(1., 0., 0.)) };
+        let t2 = vh.cross(t1);
+        let p = UniformDisk::warp(s);
+        let h = 0.5 * (1. + vh.0[Z]);
+        let p = A2(p[X], LinearScale::interp(A2(F::sqrt(1. - p[X].sq()), p[Y]), h));
+        let nh = t1 * p[X] + t2 * p[Y] + vh * F::sqrt(F::max(0., 1. - F2::dot(p, p)));
+        unstretch(nh.0, alpha)
+    }
+
+    #[inline] pub fn pdf<A, B>(s: A, wo: B, alpha: F2) -> F
+        where A: Copy + Conv<F3>, B: Copy + Conv<F3>
+    {
+        let m = TrowbridgeReitz::anisotropic(alpha);
+        m.g1(wo) * F3::dot(wo.conv(), s.conv()).max(0.) * m.d(s) / Frame::ct(wo)
+    }
+}
+
+// Visible normals as the reflection of a spherical cap (Dupuy and Benyoub,
+// "Sampling Visible GGX Normals with Spherical Caps")
+impl TrowbridgeReitzVisibleCap {
+    #[inline] pub fn warp<A: Conv<F3>>(s: F2, wo: A, alpha: F2) -> F3 {
+        let vh = stretch(wo.conv(), alpha);
+        let z = (1. - s[Y]).mul_add(1. + vh.0[Z], -vh.0[Z]);
+        let phi = F::TWO_PI * s[X];
+        let st = F::sqrt((1. - z.sq()).clamp(0., 1.));
+        unstretch(A3(st * phi.cos(), st * phi.sin(), z) + vh.0, alpha)
+    }
+
+    // The stretched normal reflects the stretched outgoing direction onto the cap
+    #[inline] pub fn inverse_warp<A, B>(v: A, wo: B, alpha: F2) -> F2
+        where A: Conv<F3>, B: Conv<F3>
+    {
+        let vh = stretch(wo.conv(), alpha);
+        let n = stretch(v.conv(), alpha.inv());
+        let c = (n * (2. * n.dot(vh)) - vh).0;
+        A2(azimuth(c), 1. - (c[Z] + vh.0[Z]) / (1. + vh.0[Z]))
+    }
+
+    #[inline(always)] pub fn pdf<A, B>(s: A, wo: B, alpha: F2) -> F
+        where A: Copy + Conv<F3>, B: Copy + Conv<F3>
+    { TrowbridgeReitzVisible::pdf(s, wo, alpha) }
+}
+
+// Uniform directions within the solid angle subtended by a triangle with
+// vertices relative to the origin (Arvo, "Stratified Sampling of Spherical
+// Triangles", with the numerical refinements of pbrt-v4)
+#[allow(clippy::many_single_char_names)]
+impl SphericalTriangle {
+    #[inline] pub fn solid_angle(tri: A3<F3>) -> F {
+        let A3(a, b, c) = tri.map(|v| V(v).unit());
+        2. * F::atan2(a.dot(b * c).abs(), 1. + a.dot(b) + b.dot(c) + c.dot(a))
+    }
+
+    #[inline] pub fn warp(s: F2, tri: A3<F3>) -> F3 {
+        let A3(a, b, c) = tri.map(|v| V(v).unit());
+        let (n_ab, n_ca) = ((a * b).unit(), (c * a).unit());
+        let alpha = angle_between(n_ab, -n_ca);
+        let area_pi = Self::solid_angle(tri) + F::PI;
+        let phi = LinearScale::interp(A2(F::PI, area_pi), s[X]) - alpha;
+        let (sin_phi, cos_phi) = (phi.sin(), phi.cos());
+        let (sin_alpha, cos_alpha) = (alpha.sin(), alpha.cos());
+        let k1 = cos_phi + cos_alpha;
+        let k2 = sin_alpha.mul_add(-a.dot(b), sin_phi);
+        let cos_bp = (difference_of_products(k2, cos_phi, k1, sin_phi).mul_add(cos_alpha, k2)
+                      / (difference_of_products(k2, sin_phi, -k1, cos_phi) * sin_alpha))
+                     .clamp(-1., 1.);
+        let cp = a * cos_bp + orthonormal(c, a) * F::sqrt((1. - cos_bp.sq()).max(0.));
+        let ct = s[Y].mul_add(cp.dot(b) - 1., 1.);
+        (b * ct + orthonormal(cp, b) * F::sqrt((1. - ct.sq()).max(0.))).0
+    }
+
+    #[inline] pub fn inverse_warp<A: Conv<F3>>(v: A, tri: A3<F3>) -> F2 {
+        let w = V(v.conv()).unit();
+        let A3(a, b, c) = tri.map(|v| V(v).unit());
+        let (n_ab, n_ca) = ((a * b).unit(), (c * a).unit());
+        let alpha = angle_between(n_ab, -n_ca);
+        let cp = ((b * w) * (c * a)).unit();
+        let cp = if cp.dot(a + c) < 0. { -cp } else { cp };
+        let (n_cpb, n_acp) = (cp * b, a * cp);
+        let u = if n_cpb.norm2() == 0. || n_acp.norm2() == 0. { 0. } else {
+            let (n_cpb, n_acp) = (n_cpb.unit(), n_acp.unit());
+            let area = alpha + angle_between(n_ab, n_cpb) + angle_between(n_acp, -n_cpb) - F::PI;
+            area / Self::solid_angle(tri)
+        };
+        A2(u, (1. - w.dot(b)) / (1. - cp.dot(b))).map(|u| u.clamp(0., 1.))
+    }
+
+    #[inline] pub fn pdf<A: Conv<F3>>(v: A, tri: A3<F3>) -> F {
+        let w = V(v.conv());
+        let A3(a, b, c) = tri.map(V);
+        let o = a.dot(b * c);
+        let inside = [w.dot(a * b), w.dot(b * c), w.dot(c * a)].iter().all(|d| d * o >= 0.);
+        if inside && o != 0. { Self::solid_angle(tri).inv() } else { 0. }
+    }
+}
+
+// Uniform directions within the solid angle subtended by the rectangle at
+// `q` relative to the origin with orthogonal edges `ex` and `ey` (Ureña et
+// al., "An Area-Preserving Parametrization for Spherical Rectangles")
+#[allow(clippy::many_single_char_names)]
+impl SphericalRectangle {
+    #[inline] pub fn solid_angle(q: F3, ex: F3, ey: F3) -> F {
+        let r = Rectangle::new(q, ex, ey);
+        r.solid_angle(r.x1)
+    }
+
+    #[inline] pub fn warp(s: F2, q: F3, ex: F3, ey: F3) -> F3 {
+        let r = Rectangle::new(q, ex, ey);
+        let (b0, b1, k) = r.coefficients();
+        let au = s[X].mul_add(r.solid_angle(r.x1), k);
+        let fu = au.cos().mul_add(b0, -b1) / au.sin();
+        let cu = (fu.hypot(b0).inv().copysign(fu)).clamp(-1., 1.);
+        let xu = (-(cu * r.z0) / F::sqrt((1. - cu.sq()).max(0.))).clamp(r.x0, r.x1);
+        let d = xu.hypot(r.z0);
+        let (h0, h1) = (r.y0 / d.hypot(r.y0), r.y1 / d.hypot(r.y1));
+        let hv = LinearScale::interp(A2(h0, h1), s[Y]);
+        let yv = if hv.sq() < 1. - F::EPS { hv * d / F::sqrt(1. - hv.sq()) } else { r.y1 };
+        (r.x * xu + r.y * yv + r.z * r.z0).unit().0
+    }
+
+    #[inline] pub fn inverse_warp<A: Conv<F3>>(v: A, q: F3, ex: F3, ey: F3) -> F2 {
+        let r = Rectangle::new(q, ex, ey);
+        let w = V(v.conv());
+        let t = r.z0 / w.dot(r.z);
+        let (xu, yv) = ((t * w.dot(r.x)).clamp(r.x0, r.x1), (t * w.dot(r.y)).clamp(r.y0, r.y1));
+        let d = xu.hypot(r.z0);
+        let (h0, h1) = (r.y0 / d.hypot(r.y0), r.y1 / d.hypot(r.y1));
+        A2(r.solid_angle(xu) / r.solid_angle(r.x1), (yv / d.hypot(yv) - h0) / (h1 - h0))
+    }
+
+    #[inline] pub fn pdf<A: Conv<F3>>(v: A, q: F3, ex: F3, ey: F3) -> F {
+        let r = Rectangle::new(q, ex, ey);
+        let w = V(v.conv());
+        let t = r.z0 / w.dot(r.z);
+        let (xu, yv) = (t * w.dot(r.x), t * w.dot(r.y));
+        let inside = t > 0. && (r.x0..=r.x1).contains(&xu) && (r.y0..=r.y1).contains(&yv);
+        if inside { r.solid_angle(r.x1).inv() } else { 0. }
+    }
+}
+
+// The rectangle in the frame of its edges, facing away from the origin
+#[derive(Clone, Copy, Debug)]
+struct Rectangle {
+    x:  V,
+    y:  V,
+    z:  V,
+    x0: F,
+    x1: F,
+    y0: F,
+    y1: F,
+    z0: F,
+}
+
+impl Rectangle {
+    #[inline] fn new(q: F3, ex: F3, ey: F3) -> Self {
+        let (q, ex, ey) = (V(q), V(ex), V(ey));
+        let (x, y) = (ex.unit(), ey.unit());
+        let z = x * y;
+        let (z, z0) = if q.dot(z) > 0. { (-z, -q.dot(z)) } else { (z, q.dot(z)) };
+        let (x0, y0) = (q.dot(x), q.dot(y));
+        Self { x, y, z, x0, x1: x0 + ex.norm(), y0, y1: y0 + ey.norm(), z0 }
+    }
+
+    // Inner normals of the spherical rectangle up to `x1`
+    #[inline] fn normals(&self, x1: F) -> A4<V> {
+        let v = |x, y| V(A3(x, y, self.z0));
+        let (v00, v01, v10, v11) = (v(self.x0, self.y0), v(self.x0, self.y1),
+                                    v(x1, self.y0), v(x1, self.y1));
+        A4(v00 * v10, v10 * v11, v11 * v01, v01 * v00).map(V::unit)
+    }
+
+    #[inline] fn coefficients(&self) -> (F, F, F) {
+        let n = self.normals(self.x1);
+        let g2 = F::acos((-n.2.dot(n.3)).clamp(-1., 1.));
+        let g3 = F::acos((-n.3.dot(n.0)).clamp(-1., 1.));
+        (n.0[Z], n.2[Z], F::TWO_PI - g2 - g3)
+    }
+
+    // Solid angle of the part of the rectangle up to `x1`
+    #[inline] fn solid_angle(&self, x1: F) -> F {
+        if x1 <= self.x0 || self.z0 == 0. { return 0. }
+        let n = self.normals(x1);
+        let g = A4(n.0.dot(n.1), n.1.dot(n.2), n.2.dot(n.3), n.3.dot(n.0))
+                  .map(|c| F::acos((-c).clamp(-1., 1.)));
+        g.sum() - F::TWO_PI
+    }
+}
+
+// Points in [0, 1)^2 with density proportional to the bilinear interpolation
+// of the corner weights at (0, 0), (1, 0), (0, 1) and (1, 1), e.g. cosines at
+// the corners of a spherical rectangle sample as a cosine-weighted approximation
+impl Bilinear {
+    #[inline] pub fn warp(s: F2, w: F4) -> F2 {
+        if w.sum() == 0. { return s }
+        let y = sample_linear(s[Y], w.0 + w.1, w.2 + w.3);
+        A2(sample_linear(s[X], LinearScale::interp(A2(w.0, w.2), y),
+                         LinearScale::interp(A2(w.1, w.3), y)), y)
+    }
+
+    #[inline] pub fn inverse_warp(p: F2, w: F4) -> F2 {
+        if w.sum() == 0. { return p }
+        A2(invert_linear(p[X], LinearScale::interp(A2(w.0, w.2), p[Y]),
+                         LinearScale::interp(A2(w.1, w.3), p[Y])),
+           invert_linear(p[Y], w.0 + w.1, w.2 + w.3))
+    }
+
+    #[inline] pub fn pdf(p: F2, w: F4) -> F {
+        if !(0. ..=1.).contains(&p[X]) || !(0. ..=1.).contains(&p[Y]) { return 0. }
+        if w.sum() == 0. { return 1. }
+        let b = LinearScale::interp(A2(LinearScale::interp(A2(w.0, w.1), p[X]),
+                                       LinearScale::interp(A2(w.2, w.3), p[X])), p[Y]);
+        4. * b / w.sum()
+    }
+}
+
+impl Warp for UniformTriangle {
+    type Sample = F2; type Output = F2; type Param = ();
+    #[inline(always)] fn warp(&self, s: F2, (): ()) -> F2 { Self::warp(s) }
+    #[inline(always)] fn pdf(&self, p: F2, (): ()) -> F
+    { if p[X] >= 0. && p[Y] >= 0. && p[X] + p[Y] <= 1. { Self::pdf() } else { 0. } }
+}
+
+impl Warp for UniformDisk {
+    type Sample = F2; type Output = F2; type Param = ();
+    #[inline(always)] fn warp(&self, s: F2, (): ()) -> F2 { Self::warp(s) }
+    #[inline(always)] fn pdf(&self, p: F2, (): ()) -> F
+    { if F2::dot(p, p) <= 1. { Self::pdf() } else { 0. } }
+}
+
+impl Warp for CosineHemisphere {
+    type Sample = F2; type Output = F3; type Param = ();
+    #[inline(always)] fn warp(&self, s: F2, (): ()) -> F3 { Self::warp(s) }
+    #[inline(always)] fn pdf(&self, v: F3, (): ()) -> F { Self::pdf(v) }
+}
+
+impl Warp for UniformCylinder {
+    type Sample = F2; type Output = F3; type Param = ();
+    #[inline(always)] fn warp(&self, s: F2, (): ()) -> F3 { Self::warp(s) }
+    #[inline(always)] fn pdf(&self, _: F3, (): ()) -> F { Self::pdf() }
+}
+
+impl Warp for UniformSphere {
+    type Sample = F2; type Output = F3; type Param = ();
+    #[inline(always)] fn warp(&self, s: F2, (): ()) -> F3 { Self::warp(s) }
+    #[inline(always)] fn pdf(&self, _: F3, (): ()) -> F { Self::pdf() }
+}
+
+impl Warp for UniformHemisphere {
+    type Sample = F2; type Output = F3; type Param = ();
+    #[inline(always)] fn warp(&self, s: F2, (): ()) -> F3 { Self::warp(s) }
+    #[inline(always)] fn pdf(&self, v: F3, (): ()) -> F
+    { if v[Z] >= 0. { Self::pdf() } else { 0. } }
+}
+
+impl Warp for UniformCone {
+    type Sample = F2; type Output = F3; type Param = F;
+    #[inline(always)] fn warp(&self, s: F2, ct_max: F) -> F3 { Self::warp(s, ct_max) }
+    #[inline(always)] fn pdf(&self, v: F3, ct_max: F) -> F { Self::pdf(v, ct_max) }
+}
+
+impl Warp for SphericalTriangle {
+    type Sample = F2; type Output = F3; type Param = A3<F3>;
+    #[inline(always)] fn warp(&self, s: F2, tri: A3<F3>) -> F3 { Self::warp(s, tri) }
+    #[inline(always)] fn pdf(&self, v: F3, tri: A3<F3>) -> F { Self::pdf(v, tri) }
+}
+
+impl Warp for SphericalRectangle {
+    type Sample = F2; type Output = F3; type Param = A3<F3>;
+    #[inline(always)] fn warp(&self, s: F2, A3(q, ex, ey): A3<F3>) -> F3
+    { Self::warp(s, q, ex, ey) }
+    #[inline(always)] fn pdf(&self, v: F3, A3(q, ex, ey): A3<F3>) -> F
+    { Self::pdf(v, q, ex, ey) }
+}
+
+impl Warp for Bilinear {
+    type Sample = F2; type Output = F2; type Param = F4;
+    #[inline(always)] fn warp(&self, s: F2, w: F4) -> F2 { Self::warp(s, w) }
+    #[inline(always)] fn pdf(&self, p: F2, w: F4) -> F { Self::pdf(p, w) }
+}
+
+impl Warp for BeckmannHemisphere {
+    type Sample = F2; type Output = F3; type Param = F;
+    #[inline(always)] fn warp(&self, s: F2, alpha: F) -> F3 { Self::warp(s, alpha) }
+    #[inline(always)] fn pdf(&self, v: F3, alpha: F) -> F { Self::pdf(v, alpha) }
+}
+
+impl Warp for TrowbridgeReitzHemisphere {
+    type Sample = F2; type Output = F3; type Param = F;
+    #[inline(always)] fn warp(&self, s: F2, alpha: F) -> F3 { Self::warp(s, alpha) }
+    #[inline(always)] fn pdf(&self, v: F3, alpha: F) -> F { Self::pdf(v, alpha) }
+}
+
+// Parameterized by the outgoing direction and the roughness
+impl Warp for TrowbridgeReitzVisible {
+    type Sample = F2; type Output = F3; type Param = (F3, F2);
+    #[inline(always)] fn warp(&self, s: F2, (wo, alpha): (F3, F2)) -> F3
+    { Self::warp(s, wo, alpha) }
+    #[inline(always)] fn pdf(&self, v: F3, (wo, alpha): (F3, F2)) -> F
+    { Self::pdf(v, wo, alpha) }
+}
+
+impl Warp for TrowbridgeReitzVisibleCap {
+    type Sample = F2; type Output = F3; type Param = (F3, F2);
+    #[inline(always)] fn warp(&self, s: F2, (wo, alpha): (F3, F2)) -> F3
+    { Self::warp(s, wo, alpha) }
+    #[inline(always)] fn pdf(&self, v: F3, (wo, alpha): (F3, F2)) -> F
+    { Self::pdf(v, wo, alpha) }
+}
+
+impl Warp for IsotropicPhase {
+    type Sample = F2; type Output = F3; type Param = ();
+    #[inline(always)] fn warp(&self, s: F2, (): ()) -> F3 { Self::warp(s) }
+    #[inline(always)] fn pdf(&self, _: F3, (): ()) -> F { Self::pdf() }
+}
+
+// Parameterized by the asymmetry and the outgoing direction
+impl Warp for HenyeyGreenstein {
+    type Sample = F2; type Output = F3; type Param = (F, F3);
+    #[inline(always)] fn warp(&self, s: F2, (g, wo): (F, F3)) -> F3 { Self::warp(s, g, wo) }
+    #[inline(always)] fn pdf(&self, v: F3, (g, wo): (F, F3)) -> F { Self::pdf(v, g, wo) }
+}
+
+impl Warp for TwoLobeHenyeyGreenstein {
+    type Sample = F2; type Output = F3; type Param = (F2, F, F3);
+    #[inline(always)] fn warp(&self, s: F2, (g, w, wo): (F2, F, F3)) -> F3
+    { Self::warp(s, g, w, wo) }
+    #[inline(always)] fn pdf(&self, v: F3, (g, w, wo): (F2, F, F3)) -> F
+    { Self::pdf(v, g, w, wo) }
+}
+
+impl Warp for Rayleigh {
+    type Sample = F2; type Output = F3; type Param = F3;
+    #[inline(always)] fn warp(&self, s: F2, wo: F3) -> F3 { Self::warp(s, wo) }
+    #[inline(always)] fn pdf(&self, v: F3, wo: F3) -> F { Self::pdf(v, wo) }
+}
+
+impl InverseWarp for UniformTriangle
+{ #[inline(always)] fn inverse_warp(&self, p: F2, (): ()) -> F2 { Self::inverse_warp(p) } }
+
+impl InverseWarp for UniformDisk
+{ #[inline(always)] fn inverse_warp(&self, p: F2, (): ()) -> F2 { Self::inverse_warp(p) } }
+
+impl InverseWarp for CosineHemisphere
+{ #[inline(always)] fn inverse_warp(&self, v: F3, (): ()) -> F2 { Self::inverse_warp(v) } }
+
+impl InverseWarp for UniformCylinder
+{ #[inline(always)] fn inverse_warp(&self, v: F3, (): ()) -> F2 { Self::inverse_warp(v) } }
+
+impl InverseWarp for UniformSphere
+{ #[inline(always)] fn inverse_warp(&self, v: F3, (): ()) -> F2 { Self::inverse_warp(v) } }
+
+impl InverseWarp for UniformHemisphere
+{ #[inline(always)] fn inverse_warp(&self, v: F3, (): ()) -> F2 { Self::inverse_warp(v) } }
+
+impl InverseWarp for UniformCone {
+    #[inline(always)] fn inverse_warp(&self, v: F3, ct_max: F) -> F2
+    { Self::inverse_warp(v, ct_max) }
+}
+
+impl InverseWarp for SphericalTriangle {
+    #[inline(always)] fn inverse_warp(&self, v: F3, tri: A3<F3>) -> F2
+    { Self::inverse_warp(v, tri) }
+}
+
+impl InverseWarp for SphericalRectangle {
+    #[inline(always)] fn inverse_warp(&self, v: F3, A3(q, ex, ey): A3<F3>) -> F2
+    { Self::inverse_warp(v, q, ex, ey) }
+}
+
+impl InverseWarp for Bilinear
+{ #[inline(always)] fn inverse_warp(&self, p: F2, w: F4) -> F2 { Self::inverse_warp(p, w) } }
+
+impl InverseWarp for BeckmannHemisphere {
+    #[inline(always)] fn inverse_warp(&self, v: F3, alpha: F) -> F2
+    { Self::inverse_warp(v, alpha) }
+}
+
+impl InverseWarp for TrowbridgeReitzHemisphere {
+    #[inline(always)] fn inverse_warp(&self, v: F3, alpha: F) -> F2
+    { Self::inverse_warp(v, alpha) }
+}
+
+impl InverseWarp for TrowbridgeReitzVisibleCap {
+    #[inline(always)] fn inverse_warp(&self, v: F3, (wo, alpha): (F3, F2)) -> F2
+    { Self::inverse_warp(v, wo, alpha) }
+}
+
+impl InverseWarp for IsotropicPhase
+{ #[inline(always)] fn inverse_warp(&self, v: F3, (): ()) -> F2 { Self::inverse_warp(v) } }
+
+impl InverseWarp for HenyeyGreenstein {
+    #[inline(always)] fn inverse_warp(&self, v: F3, (g, wo): (F, F3)) -> F2
+    { Self::inverse_warp(v, g, wo) }
+}
+
+impl InverseWarp for Rayleigh
+{ #[inline(always)] fn inverse_warp(&self, v: F3, wo: F3) -> F2 { Self::inverse_warp(v, wo) } }
+
+// Phase functions take both directions pointing away from the scattering
+// point, so forward scattering has `wi = -wo`
+impl IsotropicPhase {
+    #[inline(always)] pub fn warp(s: F2) -> F3 { UniformSphere::warp(s) }
+
+    #[inline(always)] pub fn inverse_warp<A: Conv<F3>>(v: A) -> F2
+    { UniformSphere::inverse_warp(v) }
+
+    #[inline(always)] pub const fn pdf() -> F { F::INV_4PI }
+}
+
+impl HenyeyGreenstein {
+    #[inline(always)] pub fn eval(ct: F, g: F) -> F {
+        let d = (2. * g).mul_add(ct, g.mul_add(g, 1.));
+        F::INV_4PI * (1. - g.sq()) / (d * d.max(0.).sqrt())
+    }
+
+    #[inline] pub fn warp<A: Conv<F3>>(s: F2, g: F, wo: A) -> F3 {
+        let ct = if g.abs() < 1e-3 { s[X].mul_add(-2., 1.) } else {
+            let sq = (1. - g.sq()) / (2. * g).mul_add(-s[X], 1. + g);
+            (g.mul_add(g, 1.) - sq.sq()) / (2. * g)
+        };
+        about(-V(wo.conv()), ct, s[Y])
+    }
+
+    #[inline] pub fn inverse_warp<A, B>(v: A, g: F, wo: B) -> F2
+        where A: Conv<F3>, B: Conv<F3>
+    {
+        let (ct, s) = inverse_about(-V(wo.conv()), v.conv());
+        if g.abs() < 1e-3 { return A2((1. - ct) * 0.5, s) }
+        let sq = F::sqrt((2. * g).mul_add(-ct, g.mul_add(g, 1.)));
+        A2((1. + g - (1. - g.sq()) / sq) / (2. * g), s)
+    }
+
+    #[inline(always)] pub fn pdf<A, B>(s: A, g: F, wo: B) -> F
+        where A: Conv<F3>, B: Conv<F3>
+    { Self::eval(F3::dot(s.conv(), wo.conv()), g) }
+}
+
+// Mixture of two lobes, the first weighted by `w`
+impl TwoLobeHenyeyGreenstein {
+    #[inline] pub fn warp<A: Conv<F3>>(s: F2, g: F2, w: F, wo: A) -> F3 {
+        if s[X] < w { HenyeyGreenstein::warp(A2(s[X] / w, s[Y]), g[X], wo) }
+        else { HenyeyGreenstein::warp(A2((s[X] - w) / (1. - w), s[Y]), g[Y], wo) }
+    }
+
+    #[inline(always)] pub fn pdf<A, B>(s: A, g: F2, w: F, wo: B) -> F
+        where A: Conv<F3>, B: Conv<F3>
+    {
+        let ct = F3::dot(s.conv(), wo.conv());
+        LinearScale::interp(A2(HenyeyGreenstein::eval(ct, g[Y]),
+                               HenyeyGreenstein::eval(ct, g[X])), w)
+    }
+}
+
+impl Rayleigh {
+    // Closed-form inverse of the cubic cdf of cos(theta)
+    #[inline] pub fn warp<A: Conv<F3>>(s: F2, wo: A) -> F3 {
+        let z = s[X].mul_add(4., -2.);
+        let a = F::cbrt(z + z.mul_add(z, 1.).sqrt());
+        about(V(wo.conv()), (a - a.inv()).clamp(-1., 1.), s[Y])
+    }
+
+    #[inline] pub fn inverse_warp<A, B>(v: A, wo: B) -> F2
+        where A: Conv<F3>, B: Conv<F3>
+    {
+        let (ct, s) = inverse_about(V(wo.conv()), v.conv());
+        A2(ct.mul_add(ct.mul_add(ct, 3.), 4.) * 0.125, s)
+    }
+
+    #[inline(always)] pub fn pdf<A, B>(s: A, wo: B) -> F
+        where A: Conv<F3>, B: Conv<F3>
+    { 3. / 16. * F::INV_PI * (1. + F3::dot(s.conv(), wo.conv()).sq()) }
+}
+
+// Direction at polar angle `acos(ct)` and azimuth `2 pi s` about `axis`
+#[inline] fn about(axis: V, ct: F, s: F) -> F3 {
+    let st = F::sqrt((1. - ct.sq()).max(0.));
+    let phi = F::TWO_PI * s;
+    ShadingFrame::new(axis.conv()).to_world(A3(st * phi.cos(), st * phi.sin(), ct))
+}
+
+// Cosine of the polar angle and azimuth sample of `v` about `axis`
+#[inline] fn inverse_about(axis: V, v: F3) -> (F, F) {
+    let v = ShadingFrame::new(axis.conv()).to_local(v);
+    (v[Z].clamp(-1., 1.), azimuth(v))
+}
+
+#[inline(always)] fn azimuth(v: F3) -> F { Frame::cart2spher(v)[Y] * F::INV_2PI }
+
+// Numerically robust angle between unit vectors
+#[inline(always)] fn angle_between(a: V, b: V) -> F {
+    if a.dot(b) < 0. { F::asin(((a + b).norm() * 0.5).min(1.)).mul_add(-2., F::PI) }
+    else { 2. * F::asin(((b - a).norm() * 0.5).min(1.)) }
+}
+
+// Unit component of `v` orthogonal to the unit vector `w`
+#[inline(always)] fn orthonormal(v: V, w: V) -> V { (v - w * v.dot(w)).unit() }
+
+// Position in [0, 1) with density proportional to the line from `a` to `b`,
+// uniform if both are zero
+#[inline] fn sample_linear(s: F, a: F, b: F) -> F {
+    if a + b == 0. { return s }
+    if s == 0. && a == 0. { return 0. }
+    let x = s * (a + b) / (a + F::sqrt(LinearScale::interp(A2(a.sq(), b.sq()), s)));
+    x.min(F::ONE_MINUS_EPS)
+}
+
+#[inline(always)] fn invert_linear(x: F, a: F, b: F) -> F
+{ if a + b == 0. { x } else { x * a.mul_add(2. - x, b * x) / (a + b) } }
+
+// Maps to and from the configuration of a unit-roughness distribution
+#[inline(always)] fn stretch(w: F3, alpha: F2) -> V
+{ V(A3(w[X] * alpha[X], w[Y] * alpha[Y], w[Z])).unit() }
+
+#[inline(always)] fn unstretch(n: F3, alpha: F2) -> F3
+{ V(A3(n[X] * alpha[X], n[Y] * alpha[Y], n[Z].max(0.))).unit().0 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::*;
+    use crate::{EXAMPLE_RECTANGLE as RECT, EXAMPLE_TRIANGLE as TRI, EXAMPLE_WO as WO};
+
+    #[test] fn below_horizon() {
+        let v = Frame::spher2cart(A2(2.5, 0.7));
+        assert!(CosineHemisphere::pdf(v) == 0.);
+        assert!(BeckmannHemisphere::pdf(v, 0.3) == 0.);
+        assert!(TrowbridgeReitzHemisphere::pdf(v, 0.3) == 0.);
+    }
+
+    #[test] fn trowbridge_reitz_hemisphere() {
+        check(|s| TrowbridgeReitzHemisphere::warp(s, 0.3),
+              |w| TrowbridgeReitzHemisphere::pdf(w, 0.3));
+    }
+
+    #[test] fn trowbridge_reitz_visible() {
+        for &(wo, alpha) in &[(A2(0.3, 0.), A2(0.3, 0.3)), (A2(1.2, 2.), A2(0.2, 0.6)),
+                              (A2(0., 0.), A2(0.5, 0.5))] {
+            let wo = Frame::spher2cart(wo);
+            check(|s| TrowbridgeReitzVisible::warp(s, wo, alpha),
+                  |w| TrowbridgeReitzVisible::pdf(w, wo, alpha));
+            check(|s| TrowbridgeReitzVisibleCap::warp(s, wo, alpha),
+                  |w| TrowbridgeReitzVisibleCap::pdf(w, wo, alpha));
+        }
+    }
+
+    fn round_trip<A>(warp: impl Fn(F2) -> A, inverse: impl Fn(A) -> F2) {
+        for i in 1..20 {
+            for j in 1..20 {
+                let s = A2(F::of(i), F::of(j)) * 0.05;
+                let t = inverse(warp(s));
+                assert!((t - s).map(F::abs).reduce(F::max) < 1e-3, "{:?} {:?}", s, t);
+            }
+        }
+    }
+
+    fn trait_round_trip<W>(w: &W, p: W::Param) where W: InverseWarp<Sample=F2> {
+        round_trip(|s| w.warp(s, p), |x| w.inverse_warp(x, p));
+    }
+
+    #[test] fn warp_trait() {
+        trait_round_trip(&UniformDisk, ());
+        trait_round_trip(&BeckmannHemisphere, 0.3);
+        trait_round_trip(&HenyeyGreenstein, (0.3, WO));
+        let (s, wo) = (A2(0.3, 0.7), A3(0., 0.6, 0.8));
+        let v = Warp::warp(&TrowbridgeReitzVisible, s, (wo, A2(0.3, 0.5)));
+        assert_eq!(v, TrowbridgeReitzVisible::warp(s, wo, A2(0.3, 0.5)));
+        assert!((Warp::pdf(&TrowbridgeReitzVisible, v, (wo, A2(0.3, 0.5)))
+                 - TrowbridgeReitzVisible::pdf(v, wo, A2(0.3, 0.5))).abs() < F::EPS);
+    }
+
+    #[test] fn warp_trait_support() {
+        assert!(Warp::pdf(&UniformTriangle, A2(0.2, 0.3), ()) > 0.);
+        assert!(Warp::pdf(&UniformTriangle, A2(0.7, 0.6), ()) == 0.);
+        assert!(Warp::pdf(&UniformTriangle, A2(-0.1, 0.3), ()) == 0.);
+        assert!(Warp::pdf(&UniformDisk, A2(0.6, -0.7), ()) > 0.);
+        assert!(Warp::pdf(&UniformDisk, A2(0.8, -0.7), ()) == 0.);
+        assert!(Warp::pdf(&UniformHemisphere, A3(0.6, 0., 0.8), ()) > 0.);
+        assert!(Warp::pdf(&UniformHemisphere, A3(0.6, 0., -0.8), ()) == 0.);
+    }
+
+    #[test] fn inverse_warps() {
+        round_trip(UniformTriangle::warp, UniformTriangle::inverse_warp);
+        round_trip(UniformDisk::warp, UniformDisk::inverse_warp);
+        round_trip(CosineHemisphere::warp, CosineHemisphere::inverse_warp);
+        round_trip(UniformCylinder::warp, UniformCylinder::inverse_warp);
+        round_trip(UniformSphere::warp, UniformSphere::inverse_warp);
+        round_trip(IsotropicPhase::warp, IsotropicPhase::inverse_warp);
+        round_trip(|s| UniformCone::warp(s, 0.4), |v| UniformCone::inverse_warp(v, 0.4));
+        trait_round_trip(&SphericalTriangle, TRI);
+        trait_round_trip(&SphericalRectangle, RECT);
+        trait_round_trip(&Bilinear, A4(1., 0.2, 3., 0.5));
+        round_trip(|s| BeckmannHemisphere::warp(s, 0.3),
+                   |v| BeckmannHemisphere::inverse_warp(v, 0.3));
+        round_trip(|s| TrowbridgeReitzHemisphere::warp(s, 0.3),
+                   |v| TrowbridgeReitzHemisphere::inverse_warp(v, 0.3));
+        round_trip(|s| TrowbridgeReitzVisibleCap::warp(s, WO, A2(0.2, 0.6)),
+                   |v| TrowbridgeReitzVisibleCap::inverse_warp(v, WO, A2(0.2, 0.6)));
+        for &g in &[-0.5, 0., 0.3, 0.8] {
+            round_trip(|s| HenyeyGreenstein::warp(s, g, WO),
+                       |v| HenyeyGreenstein::inverse_warp(v, g, WO));
+        }
+        round_trip(|s| Rayleigh::warp(s, WO), |v| Rayleigh::inverse_warp(v, WO));
+        let s = A2(0.3, 0.6);
+        let v = UniformHemisphere::warp(s);
+        assert!((UniformHemisphere::warp(UniformHemisphere::inverse_warp(v)) - v)
+                .map(F::abs).max() < F::EPS);
+    }
+
+    #[test] fn spherical_polygons() {
+        let tri = Triangle(TRI.map(P));
+        let solid_angle = integrate(F::PI, |w| {
+            F::from(tri.intersects(R::unbounded(P::ZERO, V(w))))
+        });
+        assert!((SphericalTriangle::solid_angle(TRI) - solid_angle).abs() < 1e-2);
+        check_over(F::PI, |s| SphericalTriangle::warp(s, TRI), |w| SphericalTriangle::pdf(w, TRI));
+        let A3(q, ex, ey) = RECT;
+        let solid_angle = integrate(F::PI, |w| F::from(SphericalRectangle::pdf(w, q, ex, ey) > 0.));
+        assert!((SphericalRectangle::solid_angle(q, ex, ey) - solid_angle).abs() < 1e-2);
+        check_over(F::PI, |s| SphericalRectangle::warp(s, q, ex, ey),
+                   |w| SphericalRectangle::pdf(w, q, ex, ey));
+    }
+
+    #[test] fn bilinear() {
+        let w = A4(1., 0.2, 3., 0.5);
+        let p = Bilinear::warp(A2(0.5, 0.5), A4(1., 1., 1., 1.));
+        assert!((p - A2(0.5, 0.5)).map(F::abs).max() < F::EPS);
+        let mean = expect(|s| F3::a2a(Bilinear::warp(s, w), 0.), |p| p[Y]);
+        assert!((mean - (1.2 / 3. + 2. * 3.5 / 3.) / 4.7).abs() < 1e-3);
+        assert!((Bilinear::pdf(A2(1., 1.), w) - 4. * 0.5 / 4.7).abs() < F::EPS);
+        // all-zero weights are uniform, like the pdf
+        let s = A2(0.3, 0.8);
+        assert_eq!(Bilinear::warp(s, F4::ZERO), s);
+        assert_eq!(Bilinear::inverse_warp(s, F4::ZERO), s);
+        assert!((Bilinear::pdf(s, F4::ZERO) - 1.).abs() < F::EPS);
+        let p = Bilinear::warp(s, A4(0., 0., 1., 1.));
+        assert!(p[X].is_finite() && (p[X] - s[X]).abs() < F::EPS);
+        assert!((sample_linear(0.4, 0., 0.) - 0.4).abs() < F::EPS);
+    }
+
+    #[test] fn uniform_cone() {
+        for &ct_max in &[0.9, 0.2, -0.5] {
+            check_over(F::acos(ct_max), |s| UniformCone::warp(s, ct_max),
+                       |w| UniformCone::pdf(w, ct_max));
+        }
+        assert!(UniformCone::pdf(A3(0.8, 0., 0.6), 0.7).abs() < F::EPS);
+    }
+
+    #[test] fn isotropic_phase() {
+        check_over(F::PI, IsotropicPhase::warp, |_| IsotropicPhase::pdf());
+    }
+
+    #[test] fn henyey_greenstein() {
+        for &g in &[-0.5, 0., 0.3, 0.8] {
+            check_over(F::PI, |s| HenyeyGreenstein::warp(s, g, WO),
+                       |w| HenyeyGreenstein::pdf(w, g, WO));
+        }
+        assert!(HenyeyGreenstein::pdf(-WO, 0.5, WO) > HenyeyGreenstein::pdf(WO, 0.5, WO));
+        let ct = expect(|s| HenyeyGreenstein::warp(s, 0.6, WO), |w| -F3::dot(w, WO));
+        assert!((ct - 0.6).abs() < 1e-2);
+    }
+
+    #[test] fn two_lobe_henyey_greenstein() {
+        check_over(F::PI, |s| TwoLobeHenyeyGreenstein::warp(s, A2(0.7, -0.4), 0.3, WO),
+                   |w| TwoLobeHenyeyGreenstein::pdf(w, A2(0.7, -0.4), 0.3, WO));
+    }
+
+    #[test] fn rayleigh() {
+        check_over(F::PI, |s| Rayleigh::warp(s, WO), |w| Rayleigh::pdf(w, WO));
+    }
+}
@@ -1,6 +1,3 @@
-#![feature(array_map,
-           const_fn)]
-
 #![warn(clippy::all,
         clippy::cargo,
         clippy::nursery,
@@ -18,6 +15,8 @@
 
 mod core;
 mod geometry;
+#[cfg(test)]
+mod testing;
 
 pub use crate::core::*;
 pub use crate::geometry::*;
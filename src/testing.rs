@@ -0,0 +1,43 @@
+// Helpers shared by the unit tests
+
+use crate::*;
+
+// Largest componentwise absolute difference
+pub trait Diff: Copy { fn diff(self, b: Self) -> F; }
+
+impl Diff for F { #[inline(always)] fn diff(self, b: F) -> F { (self - b).abs() } }
+impl Diff for F3 { #[inline(always)] fn diff(self, b: F3) -> F { (self - b).map(F::abs).max() } }
+impl Diff for P { #[inline(always)] fn diff(self, b: P) -> F { self.0.diff(b.0) } }
+impl Diff for V { #[inline(always)] fn diff(self, b: V) -> F { self.0.diff(b.0) } }
+
+pub fn close<A: Diff>(a: A, b: A) -> bool { a.diff(b) < F::EPS }
+pub fn close_within<A: Diff>(a: A, b: A, eps: F) -> bool { a.diff(b) < eps }
+
+// Midpoint rule over the spherical cap up to `theta`
+pub fn integrate(theta: F, f: impl Fn(F3) -> F) -> F {
+    const N: I = 400;
+    let d = A2(theta, F::TWO_PI) / F::of(N);
+    (0..N).map(|i| (0..N).map(|j| {
+        let st = A2(F::of(i) + 0.5, F::of(j) + 0.5) * d;
+        f64::of(f(Frame::spher2cart(st)) * st[X].sin())
+    }).sum::<f64>()).sum::<f64>() as F * d.product()
+}
+
+// Midpoint rule over the samples of a warp
+pub fn expect(warp: impl Fn(F2) -> F3, f: impl Fn(F3) -> F) -> F {
+    const N: I = 256;
+    (0..N).flat_map(|i| (0..N).map(move |j| A2(F::of(i) + 0.5, F::of(j) + 0.5) / F::of(N)))
+          .map(|s| f64::of(f(warp(s)))).sum::<f64>() as F / F::of(N * N)
+}
+
+// The pdf of a warp onto the hemisphere integrates to one and weighs a test
+// function as its samples do
+pub fn check(warp: impl Fn(F2) -> F3, pdf: impl Fn(F3) -> F) {
+    check_over(F::HALF_PI, warp, pdf);
+}
+
+pub fn check_over(theta: F, warp: impl Fn(F2) -> F3, pdf: impl Fn(F3) -> F) {
+    assert!((integrate(theta, &pdf) - 1.).abs() < 1e-2);
+    let f = |w: F3| F::mul_add(-0.3, w[Y].sq(), w[X].mul_add(w[X] - 0.5, w[Z]));
+    assert!((integrate(theta, |w| f(w) * pdf(w)) - expect(warp, f)).abs() < 1e-2);
+}